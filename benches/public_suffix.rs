@@ -0,0 +1,55 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use resloc::hosts::get_registrable_domain;
+use resloc::types::types::{Host, HostType, PublicSuffixList};
+
+const PSL: &[u8] = include_bytes!("../data/psl.dat");
+
+const HOSTS: [&str; 6] = [
+    "www.example.com",
+    "a.b.example.co.uk",
+    "foo.github.io",
+    "b.c.kobe.jp",
+    "xn--85x722f.xn--55qx5d.cn",
+    "host.unlisted-tld",
+];
+
+// Building the list is what every short-lived process pays on first use.
+fn bench_load(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load");
+    group.bench_function("compiled", |b| b.iter(PublicSuffixList::builtin));
+    group.bench_function("parsed", |b| b.iter(|| PublicSuffixList::from_bytes(black_box(PSL)).unwrap()));
+    group.finish();
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let hosts: Vec<Host> = HOSTS.iter().map(|host| Host::new(host.to_string(), HostType::Domain)).collect();
+    let lists = [
+        ("compiled", PublicSuffixList::builtin()),
+        ("parsed", PublicSuffixList::from_bytes(PSL).unwrap()),
+    ];
+
+    // The trie alone, as seen by callers that already hold a lowercase ASCII host.
+    let mut group = c.benchmark_group("find");
+    for (name, list) in &lists {
+        group.bench_function(*name, |b| b.iter(|| {
+            for host in &HOSTS {
+                black_box(list.find(black_box(host.as_bytes()), false));
+            }
+        }));
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("registrable_domain");
+    for (name, list) in &lists {
+        group.bench_function(*name, |b| b.iter(|| {
+            for host in &hosts {
+                black_box(get_registrable_domain(black_box(host), Some(list), false));
+            }
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_load, bench_lookup);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+// Compiles data/psl.dat into a static trie of reversed labels, so the built-in public suffix
+// list needs neither parsing nor heap allocation at runtime. See src/public_suffix.rs.
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+#[path = "src/psl_trie.rs"]
+mod psl_trie;
+
+use psl_trie::{build_trie, PslNode};
+
+fn main() {
+    println!("cargo:rerun-if-changed=data/psl.dat");
+    println!("cargo:rerun-if-changed=src/psl_trie.rs");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let input: String = fs::read_to_string("data/psl.dat").expect("data/psl.dat is readable");
+    let (labels, nodes): (String, Vec<PslNode>) = build_trie(&input)
+        .unwrap_or_else(|rule| panic!("Public suffix rule {:?} is not a valid domain", rule));
+
+    let mut output: String = format!("static PSL_LABELS: &str = {:?};\n\nstatic PSL_NODES: [PslNode; {}] = [\n", labels, nodes.len());
+    for node in &nodes {
+        writeln!(
+            output,
+            "    PslNode {{ label_prefix: {:#x}, label_start: {}, label_len: {}, children_start: {}, children_len: {}, leaf: {} }},",
+            node.label_prefix, node.label_start, node.label_len, node.children_start, node.children_len, node.leaf
+        ).unwrap();
+    }
+    output += "];\n";
+
+    let path: PathBuf = PathBuf::from(env::var("OUT_DIR").unwrap()).join("psl_trie.rs");
+    fs::write(path, output).expect("OUT_DIR is writable");
+}
@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resloc::hosts::{host_parser, host_serializer};
+
+// Hosts are parsed both as special-scheme hosts, which go through IDNA and the IP address
+// parsers, and as opaque hosts. Either way the serialization parses back to itself.
+fuzz_target!(|data: &[u8]| {
+    let input: &str = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    for is_not_special in [false, true] {
+        let host = match host_parser(input, is_not_special) {
+            Ok(host) => host,
+            Err(_) => continue,
+        };
+
+        let serialized: String = host_serializer(&host);
+        let reparsed = host_parser(&serialized, is_not_special).expect("serialized host does not parse");
+        assert_eq!(host_serializer(&reparsed), serialized);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resloc::domains::{ipv4_parser, ipv4_serializer};
+
+fuzz_target!(|data: &[u8]| {
+    let input: &str = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    if let Ok(address) = ipv4_parser(input.to_string()) {
+        let serialized: String = ipv4_serializer(address);
+        assert_eq!(ipv4_parser(serialized.clone()), Ok(address), "{}", serialized);
+    }
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resloc::domains::ipv6_serializer;
+use resloc::hosts::ipv6_parser;
+
+// Input is an address without the surrounding brackets, as the host parser passes it.
+fuzz_target!(|data: &[u8]| {
+    let input: &str = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    if let Ok(address) = ipv6_parser(input.to_string()) {
+        let serialized: String = ipv6_serializer(address);
+        assert_eq!(ipv6_parser(serialized.clone()), Ok(address), "{}", serialized);
+    }
+});
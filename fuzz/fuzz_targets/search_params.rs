@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resloc::types::types::URLSearchParams;
+
+// Whatever pairs a query string parses to, their serialization parses back to the same pairs.
+fuzz_target!(|data: &[u8]| {
+    let input: &str = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    let params: URLSearchParams = URLSearchParams::from_string(input.to_string());
+    let reparsed: URLSearchParams = URLSearchParams::from_string(params.serialize());
+    assert_eq!(reparsed.pairs(), params.pairs());
+});
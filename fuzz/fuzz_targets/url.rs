@@ -0,0 +1,22 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resloc::types::types::URL;
+
+// Parsing a URL's serialization gives back the same URL, so serializing is idempotent:
+// parse(serialize(parse(x))) == parse(x).
+fuzz_target!(|data: &[u8]| {
+    let input: &str = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+
+    let url: URL = match URL::parse(input) {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+
+    let serialized: String = url.serialize(None);
+    let reparsed: URL = URL::parse(&serialized).expect("serialized URL does not parse");
+    assert_eq!(reparsed.serialize(None), serialized);
+});
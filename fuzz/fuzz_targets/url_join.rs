@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use resloc::types::types::URL;
+
+// The first line is the base URL and the rest is parsed against it. Tabs and newlines are
+// removed by the parser, so the base never needs one.
+fuzz_target!(|data: &[u8]| {
+    let (base, input): (&str, &str) = match std::str::from_utf8(data).ok().and_then(|data| data.split_once('\n')) {
+        Some(parts) => parts,
+        None => return,
+    };
+
+    let base: URL = match URL::parse(base) {
+        Ok(base) => base,
+        Err(_) => return,
+    };
+
+    let url: URL = match base.join(input) {
+        Ok(url) => url,
+        Err(_) => return,
+    };
+
+    let serialized: String = url.serialize(None);
+    let reparsed: URL = URL::parse(&serialized).expect("serialized URL does not parse");
+    assert_eq!(reparsed.serialize(None), serialized);
+
+    // A resolved URL is absolute, so resolving its serialization against the base changes nothing.
+    assert_eq!(base.join(&serialized).expect("serialized URL does not resolve").serialize(None), serialized);
+});
@@ -0,0 +1,123 @@
+use crate::domains;
+use crate::hosts::{get_registrable_domain, parse_ip_address};
+use crate::types::types::{Host, HostType};
+
+// Lowercase A-label form without a trailing root dot, so that U-label and A-label spellings of
+// the same name compare equal.
+fn to_ascii(name: &str) -> Option<String> {
+    let name: &str = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() {
+        return None;
+    }
+
+    domains::domain_to_ascii(name.to_string(), false).ok()
+}
+
+impl Host {
+    // Reference: https://www.rfc-editor.org/rfc/rfc6125#section-6.4
+    // Returns whether `name`, a DNS-ID or IP address SAN from a certificate, identifies this host.
+    // IP address SANs are compared by value, so "2001:db8::1" matches the host "[2001:DB8:0::1]".
+    // A "*" is only allowed as the whole left-most label, matches exactly one label, and never
+    // matches directly under a public suffix.
+    pub fn matches_cert_name(&self, name: &str) -> bool {
+        let domain: &str = match &self.host_type {
+            HostType::IPAddress(address) => return parse_ip_address(name).as_ref() == Some(address),
+            HostType::Domain => &self.value,
+            HostType::Opaque | HostType::Empty => return false,
+        };
+
+        let domain: String = match to_ascii(domain) {
+            Some(domain) => domain,
+            None => return false,
+        };
+
+        let (wildcard, reference): (bool, &str) = match name.strip_prefix("*.") {
+            Some(reference) => (true, reference),
+            None => (false, name),
+        };
+
+        if reference.contains('*') || parse_ip_address(reference).is_some() {
+            return false;
+        }
+
+        let reference: String = match to_ascii(reference) {
+            Some(reference) => reference,
+            None => return false,
+        };
+
+        if !wildcard {
+            return domain == reference;
+        }
+
+        // PRIVATE rules are ignored, since their owners (e.g. github.io) are issued wildcard
+        // certificates for them.
+        let reference_host = Host::new(reference.clone(), HostType::Domain);
+        if get_registrable_domain(&reference_host, None, true).is_none() {
+            return false;
+        }
+
+        match domain.split_once('.') {
+            Some((label, parent)) => !label.is_empty() && parent == reference,
+            None => false,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::host_parser;
+
+    fn host(input: &str) -> Host {
+        host_parser(input, false).unwrap()
+    }
+
+    #[test]
+    fn test_dns_names() {
+        assert!(host("www.example.com").matches_cert_name("www.example.com"));
+        assert!(host("www.example.com").matches_cert_name("WWW.Example.COM."));
+        assert!(host("www.example.com.").matches_cert_name("www.example.com"));
+        assert!(!host("www.example.com").matches_cert_name("example.com"));
+        assert!(!host("www.example.com").matches_cert_name(""));
+
+        assert!(host("b\u{FC}cher.example").matches_cert_name("xn--bcher-kva.example"));
+        assert!(host("xn--bcher-kva.example").matches_cert_name("B\u{DC}CHER.example"));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        assert!(host("www.example.com").matches_cert_name("*.example.com"));
+        assert!(host("www.example.co.uk").matches_cert_name("*.Example.co.uk"));
+        assert!(host("foo.github.io").matches_cert_name("*.github.io"));
+        assert!(host("xn--bcher-kva.example.com").matches_cert_name("*.example.com"));
+
+        assert!(!host("example.com").matches_cert_name("*.example.com"));
+        assert!(!host("a.b.example.com").matches_cert_name("*.example.com"));
+        assert!(!host("example.com").matches_cert_name("*.com"));
+        assert!(!host("example.co.uk").matches_cert_name("*.co.uk"));
+        assert!(!host("foo.unlisted").matches_cert_name("*.unlisted"));
+        assert!(!host("www.example.com").matches_cert_name("w*.example.com"));
+        assert!(!host("www.example.com").matches_cert_name("*.*.com"));
+        assert!(!host("a.www.example.com").matches_cert_name("a.*.example.com"));
+        assert!(!host("www.example.com").matches_cert_name("*"));
+        assert!(!host("www.example.com").matches_cert_name("*."));
+    }
+
+    #[test]
+    fn test_ip_addresses() {
+        assert!(host("192.0.2.1").matches_cert_name("192.0.2.1"));
+        assert!(host("0xC0.0.2.1").matches_cert_name("192.0.2.1"));
+        assert!(!host("192.0.2.1").matches_cert_name("0xC0.0.2.1"));
+        assert!(!host("192.0.2.1").matches_cert_name("192.0.2.2"));
+        assert!(!host("192.0.2.1").matches_cert_name("*.0.2.1"));
+
+        assert!(host("[2001:DB8:0::1]").matches_cert_name("2001:db8::1"));
+        assert!(host("[2001:db8::1]").matches_cert_name("[2001:0db8:0:0:0:0:0:1]"));
+        assert!(!host("[2001:db8::1]").matches_cert_name("2001:db8::2"));
+        assert!(!host("[::ffff:192.0.2.1]").matches_cert_name("192.0.2.1"));
+
+        assert!(!host("www.example.com").matches_cert_name("192.0.2.1"));
+        assert!(!host("1.2.3.4").matches_cert_name("www.example.com"));
+    }
+}
@@ -0,0 +1,92 @@
+use crate::domains;
+use crate::hosts::get_registrable_domain;
+use crate::types::types::{Host, HostType};
+
+// Reference: https://www.unicode.org/reports/tr39/#def-skeleton
+// Two strings are confusable if they have the same skeleton. A skeleton is only meant for
+// comparison and should never be shown to users. The mappings are the confusables.txt of
+// Unicode 16.0.0, as shipped in the tables of unicode-security 0.1.2, which Cargo.toml pins.
+pub fn skeleton(input: &str) -> String {
+    unicode_security::skeleton(input).collect()
+}
+
+impl Host {
+    // The skeleton of the Unicode form of a domain, so "xn--ypal-43d9g.com" has the same
+    // skeleton as "paypal.com". Returns None for hosts that are not domains.
+    pub fn skeleton(&self) -> Option<String> {
+        if !matches!(self.host_type, HostType::Domain) {
+            return None;
+        }
+
+        let (unicode, _) = domains::domain_to_unicode(self.value.clone(), false);
+        Some(skeleton(&unicode))
+    }
+
+    // True if the registrable domains of both hosts differ but look alike, e.g. "раypal.com" with
+    // a Cyrillic "ра" and "www.paypal.com". A host without a registrable domain, such as
+    // "localhost", is compared as a whole.
+    pub fn is_confusable_with(&self, other: &Host) -> bool {
+        let site = |host: &Host| -> Option<Host> {
+            if !matches!(host.host_type, HostType::Domain) {
+                return None;
+            }
+
+            let domain: String = get_registrable_domain(host, None, false).unwrap_or_else(|| host.value.to_ascii_lowercase());
+            let domain: String = domain.strip_suffix('.').unwrap_or(&domain).to_string();
+            Some(Host::new(domain, HostType::Domain))
+        };
+
+        let (site, other_site) = match (site(self), site(other)) {
+            (Some(site), Some(other_site)) => (site, other_site),
+            _ => return false,
+        };
+
+        site.value != other_site.value && site.skeleton() == other_site.skeleton()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::host_parser;
+
+    fn host(input: &str) -> Host {
+        host_parser(input, false).unwrap()
+    }
+
+    #[test]
+    fn test_skeleton() {
+        assert_eq!(unicode_security::UNICODE_VERSION, (16, 0, 0));
+
+        assert_eq!(skeleton("paypal"), "paypal");
+        assert_eq!(skeleton("\u{0440}\u{0430}ypal"), "paypal");
+        assert_eq!(skeleton("m"), "rn");
+        assert_eq!(skeleton("rn"), "rn");
+        assert_eq!(skeleton("g00gle"), "gOOgle");
+        assert_eq!(skeleton("\u{212B}"), skeleton("\u{00C5}"));
+        assert_eq!(skeleton(""), "");
+
+        assert_eq!(host("xn--ypal-43d9g.com").skeleton(), Some("paypal.corn".to_string()));
+        assert_eq!(host("127.0.0.1").skeleton(), None);
+    }
+
+    #[test]
+    fn test_is_confusable_with() {
+        let brands: Vec<Host> = ["paypal.com", "example.co.uk", "localhost"].iter().map(|brand| host(brand)).collect();
+        let is_flagged = |input: &str| brands.iter().any(|brand| host(input).is_confusable_with(brand));
+
+        assert!(is_flagged("\u{0440}\u{0430}ypal.com"));
+        assert!(is_flagged("login.\u{0440}\u{0430}ypal.com"));
+        assert!(is_flagged("paypa1.com"));
+        assert!(is_flagged("PAYPAL.corn"));
+        assert!(is_flagged("exarnple.co.uk"));
+        assert!(is_flagged("1ocalhost"));
+
+        assert!(!is_flagged("paypal.com"));
+        assert!(!is_flagged("www.paypal.com"));
+        assert!(!is_flagged("paypal.net"));
+        assert!(!is_flagged("example.com"));
+        assert!(!is_flagged("127.0.0.1"));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::domains;
+use crate::errors::CookieError;
+use crate::hosts::{get_public_suffix, host_serializer};
+use crate::types::types::{CookieDomain, Host, HostType, URL};
+
+// Reference: https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-canonicalized-host-names
+// Hosts from the URL parser are already canonical, so this is their serialization.
+fn canonicalize_host(host: &Host) -> String {
+    host_serializer(host)
+}
+
+// Reference: https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-the-domain-attribute-2
+// A leading U+002E(.) is ignored, and the rest is canonicalized like a host name.
+fn canonicalize_domain_attribute(domain: &str) -> Result<Option<String>, CookieError> {
+    let domain: &str = domain.strip_prefix('.').unwrap_or(domain);
+    if domain.is_empty() {
+        return Ok(None);
+    }
+
+    match domains::domain_to_ascii(domain.to_string(), false) {
+        Ok(domain) => Ok(Some(domain)),
+        Err(_) => Err(CookieError::InvalidDomain),
+    }
+}
+
+fn is_public_suffix(domain: &str) -> bool {
+    let host = Host::new(domain.to_string(), HostType::Domain);
+    get_public_suffix(&host, None, false).is_some_and(|public_suffix| public_suffix.value == domain)
+}
+
+// Reference: https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-domain-matching
+// `domain` must already be canonicalized. Only domains, not IP addresses, match on a suffix.
+pub fn cookie_domain_match(host: &Host, domain: &str) -> bool {
+    let host_string: String = canonicalize_host(host);
+
+    if host_string == domain {
+        return true;
+    }
+
+    (matches!(host.host_type, HostType::Domain)
+        && host_string
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.')))
+}
+
+// Reference: https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-paths-and-path-match
+pub fn default_cookie_path(url: &URL) -> String {
+    let uri_path: String = url.serialize_path();
+
+    if !uri_path.starts_with('/') || uri_path.matches('/').count() <= 1 {
+        return "/".to_string();
+    }
+
+    let rightmost_slash: usize = uri_path.rfind('/').unwrap_or(0);
+    uri_path[..rightmost_slash].to_string()
+}
+
+// Reference: https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-paths-and-path-match
+pub fn cookie_path_match(request_path: &str, cookie_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+// Reference: https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-storage-model
+// Decides which domain a cookie set by `host` is stored for, given its Domain attribute if it has
+// one. A Domain attribute that is a public suffix is only accepted when it equals the host, in
+// which case the cookie becomes host-only.
+pub fn cookie_domain(host: &Host, domain_attribute: Option<&str>) -> Result<CookieDomain, CookieError> {
+    let host_string: String = canonicalize_host(host);
+
+    let domain: String = match domain_attribute {
+        Some(domain) => match canonicalize_domain_attribute(domain)? {
+            Some(domain) => domain,
+            None => return Ok(CookieDomain::HostOnly(host_string)),
+        },
+        None => return Ok(CookieDomain::HostOnly(host_string)),
+    };
+
+    if is_public_suffix(&domain) {
+        if domain == host_string {
+            return Ok(CookieDomain::HostOnly(host_string));
+        }
+
+        return Err(CookieError::PublicSuffixDomain);
+    }
+
+    if !cookie_domain_match(host, &domain) {
+        return Err(CookieError::DomainMismatch);
+    }
+
+    Ok(CookieDomain::Domain(domain))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(input: &str) -> Host {
+        URL::parse(&format!("http://{}/", input)).unwrap().host.unwrap()
+    }
+
+    #[test]
+    fn test_cookie_domain_match() {
+        assert!(cookie_domain_match(&host("www.example.com"), "example.com"));
+        assert!(cookie_domain_match(&host("WWW.Example.com"), "www.example.com"));
+        assert!(cookie_domain_match(&host("a.b.example.com"), "example.com"));
+        assert!(!cookie_domain_match(&host("wwwexample.com"), "example.com"));
+        assert!(!cookie_domain_match(&host("example.com"), "www.example.com"));
+
+        assert!(cookie_domain_match(&host("192.0.2.1"), "192.0.2.1"));
+        assert!(cookie_domain_match(&host("0xC0.0.2.1"), "192.0.2.1"));
+        assert!(!cookie_domain_match(&host("192.0.2.1"), "0.2.1"));
+        assert!(cookie_domain_match(&host("[::1]"), "[::1]"));
+    }
+
+    #[test]
+    fn test_cookie_paths() {
+        let default_path = |input: &str| default_cookie_path(&URL::parse(input).unwrap());
+        assert_eq!(default_path("http://example.com"), "/");
+        assert_eq!(default_path("http://example.com/"), "/");
+        assert_eq!(default_path("http://example.com/a"), "/");
+        assert_eq!(default_path("http://example.com/a/b/c?q=/x"), "/a/b");
+        assert_eq!(default_path("http://example.com/a/b/"), "/a/b");
+        assert_eq!(default_path("mailto:user@example.com"), "/");
+
+        assert!(cookie_path_match("/", "/"));
+        assert!(cookie_path_match("/docs", "/docs"));
+        assert!(cookie_path_match("/docs/web", "/docs"));
+        assert!(cookie_path_match("/docs/web", "/docs/"));
+        assert!(cookie_path_match("/docs/web", "/"));
+        assert!(!cookie_path_match("/docsets", "/docs"));
+        assert!(!cookie_path_match("/doc", "/docs"));
+        assert!(!cookie_path_match("/Docs", "/docs"));
+    }
+
+    #[test]
+    fn test_cookie_domain() {
+        let host = host("www.example.co.uk");
+        assert_eq!(cookie_domain(&host, None), Ok(CookieDomain::HostOnly("www.example.co.uk".to_string())));
+        assert_eq!(cookie_domain(&host, Some("")), Ok(CookieDomain::HostOnly("www.example.co.uk".to_string())));
+        assert_eq!(cookie_domain(&host, Some(".Example.co.uk")), Ok(CookieDomain::Domain("example.co.uk".to_string())));
+        assert_eq!(cookie_domain(&host, Some("www.example.co.uk")), Ok(CookieDomain::Domain("www.example.co.uk".to_string())));
+        assert_eq!(cookie_domain(&host, Some("co.uk")), Err(CookieError::PublicSuffixDomain));
+        assert_eq!(cookie_domain(&host, Some("uk")), Err(CookieError::PublicSuffixDomain));
+        assert_eq!(cookie_domain(&host, Some("other.co.uk")), Err(CookieError::DomainMismatch));
+        assert_eq!(cookie_domain(&host, Some("a.www.example.co.uk")), Err(CookieError::DomainMismatch));
+
+        let host = super::tests::host("foo.github.io");
+        assert_eq!(cookie_domain(&host, Some("github.io")), Err(CookieError::PublicSuffixDomain));
+
+        let host = super::tests::host("github.io");
+        assert_eq!(cookie_domain(&host, Some("github.io")), Ok(CookieDomain::HostOnly("github.io".to_string())));
+
+        let host = super::tests::host("b\u{FC}cher.example.com");
+        assert_eq!(cookie_domain(&host, Some("B\u{DC}CHER.example.com")), Ok(CookieDomain::Domain("xn--bcher-kva.example.com".to_string())));
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+
+use crate::errors::DataUrlError;
+use crate::percent_encoding::percent_decode_string;
+use crate::types::types::{MimeType, URL};
+
+// Reference: https://mimesniff.spec.whatwg.org/#http-token-code-point
+fn is_http_token_code_point(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+// Reference: https://mimesniff.spec.whatwg.org/#http-quoted-string-token-code-point
+fn is_http_quoted_string_token_code_point(c: char) -> bool {
+    c == '\t' || ('\u{20}'..='\u{7E}').contains(&c) || ('\u{80}'..='\u{FF}').contains(&c)
+}
+
+// Reference: https://fetch.spec.whatwg.org/#http-whitespace
+fn is_http_whitespace(c: char) -> bool {
+    c == '\t' || c == '\n' || c == '\r' || c == ' '
+}
+
+// Reference: https://fetch.spec.whatwg.org/#collect-an-http-quoted-string
+fn collect_http_quoted_string(input: &[char], pointer: &mut usize) -> String {
+    let mut value: String = String::new();
+
+    assert_eq!(input[*pointer], '"');
+    *pointer += 1;
+
+    loop {
+        while *pointer < input.len() && input[*pointer] != '"' && input[*pointer] != '\\' {
+            value.push(input[*pointer]);
+            *pointer += 1;
+        }
+
+        if *pointer >= input.len() {
+            break;
+        }
+
+        let quote_or_backslash = input[*pointer];
+        *pointer += 1;
+
+        if quote_or_backslash == '\\' {
+            if *pointer >= input.len() {
+                value.push('\\');
+                break;
+            }
+
+            value.push(input[*pointer]);
+            *pointer += 1;
+        } else {
+            break;
+        }
+    }
+
+    value
+}
+
+impl MimeType {
+    // Reference: https://mimesniff.spec.whatwg.org/#mime-type-essence
+    pub fn essence(&self) -> String {
+        format!("{}/{}", self.type_, self.subtype)
+    }
+
+    pub fn get_parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    // Reference: https://mimesniff.spec.whatwg.org/#parse-a-mime-type
+    pub fn parse(input: &str) -> Option<MimeType> {
+        let input: Vec<char> = input
+            .trim_matches(is_http_whitespace)
+            .chars()
+            .collect();
+
+        let mut pointer: usize = 0;
+
+        let mut type_: String = String::new();
+        while pointer < input.len() && input[pointer] != '/' {
+            type_.push(input[pointer]);
+            pointer += 1;
+        }
+
+        if type_.is_empty() || !type_.chars().all(is_http_token_code_point) {
+            return None;
+        }
+
+        if pointer >= input.len() {
+            return None;
+        }
+
+        // Skip past U+002F(/).
+        pointer += 1;
+
+        let mut subtype: String = String::new();
+        while pointer < input.len() && input[pointer] != ';' {
+            subtype.push(input[pointer]);
+            pointer += 1;
+        }
+
+        let subtype: &str = subtype.trim_end_matches(is_http_whitespace);
+        if subtype.is_empty() || !subtype.chars().all(is_http_token_code_point) {
+            return None;
+        }
+
+        let mut mime_type = MimeType {
+            type_: type_.to_ascii_lowercase(),
+            subtype: subtype.to_ascii_lowercase(),
+            parameters: Vec::new(),
+        };
+
+        while pointer < input.len() {
+            // Skip past U+003B(;).
+            pointer += 1;
+
+            while pointer < input.len() && is_http_whitespace(input[pointer]) {
+                pointer += 1;
+            }
+
+            let mut parameter_name: String = String::new();
+            while pointer < input.len() && input[pointer] != ';' && input[pointer] != '=' {
+                parameter_name.push(input[pointer]);
+                pointer += 1;
+            }
+            parameter_name = parameter_name.to_ascii_lowercase();
+
+            if pointer < input.len() {
+                if input[pointer] == ';' {
+                    continue;
+                }
+
+                // Skip past U+003D(=).
+                pointer += 1;
+            }
+
+            if pointer >= input.len() {
+                break;
+            }
+
+            let parameter_value: String;
+
+            if input[pointer] == '"' {
+                parameter_value = collect_http_quoted_string(&input, &mut pointer);
+
+                while pointer < input.len() && input[pointer] != ';' {
+                    pointer += 1;
+                }
+            } else {
+                let mut value: String = String::new();
+                while pointer < input.len() && input[pointer] != ';' {
+                    value.push(input[pointer]);
+                    pointer += 1;
+                }
+
+                parameter_value = value.trim_end_matches(is_http_whitespace).to_string();
+                if parameter_value.is_empty() {
+                    continue;
+                }
+            }
+
+            if !parameter_name.is_empty()
+                && parameter_name.chars().all(is_http_token_code_point)
+                && parameter_value.chars().all(is_http_quoted_string_token_code_point)
+                && mime_type.get_parameter(&parameter_name).is_none() {
+                mime_type.parameters.push((parameter_name, parameter_value));
+            }
+        }
+
+        Some(mime_type)
+    }
+}
+
+// Reference: https://mimesniff.spec.whatwg.org/#serialize-a-mime-type
+impl fmt::Display for MimeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.essence())?;
+
+        for (name, value) in &self.parameters {
+            write!(f, ";{}=", name)?;
+
+            if value.is_empty() || !value.chars().all(is_http_token_code_point) {
+                let escaped: String = value.replace('\\', "\\\\").replace('"', "\\\"");
+                write!(f, "\"{}\"", escaped)?;
+            } else {
+                write!(f, "{}", value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn base64_value(c: char) -> Option<u32> {
+    match c {
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 26),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 52),
+        '+' => Some(62),
+        '/' => Some(63),
+        _ => None,
+    }
+}
+
+// Reference: https://infra.spec.whatwg.org/#forgiving-base64-decode
+pub fn forgiving_base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut data: Vec<char> = input
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect();
+
+    match data.len() % 4 {
+        0 if data.ends_with(&['=', '=']) => data.truncate(data.len() - 2),
+        0 if data.ends_with(&['=']) => data.truncate(data.len() - 1),
+        _ => {}
+    }
+
+    if data.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut output: Vec<u8> = Vec::with_capacity(data.len() / 4 * 3 + 2);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+
+    for c in data {
+        let value = base64_value(c)?;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+
+        if bits == 24 {
+            output.push((buffer >> 16) as u8);
+            output.push((buffer >> 8) as u8);
+            output.push(buffer as u8);
+            buffer = 0;
+            bits = 0;
+        }
+    }
+
+    match bits {
+        12 => {
+            output.push((buffer >> 4) as u8);
+        }
+        18 => {
+            output.push((buffer >> 10) as u8);
+            output.push((buffer >> 2) as u8);
+        }
+        _ => {}
+    }
+
+    Some(output)
+}
+
+// Returns true if `mime_type` ends with U+003B(;), followed by zero or more U+0020 SPACE, followed
+// by an ASCII case-insensitive match for "base64".
+fn ends_with_base64_marker(mime_type: &str) -> bool {
+    if mime_type.len() < 6 || !mime_type.is_char_boundary(mime_type.len() - 6) {
+        return false;
+    }
+
+    let (rest, marker) = mime_type.split_at(mime_type.len() - 6);
+    marker.eq_ignore_ascii_case("base64") && rest.trim_end_matches(' ').ends_with(';')
+}
+
+// Reference: https://fetch.spec.whatwg.org/#data-url-processor
+pub fn data_url_processor(data_url: &URL) -> Result<(MimeType, Vec<u8>), DataUrlError> {
+    if data_url.scheme != "data" {
+        return Err(DataUrlError::NotDataScheme);
+    }
+
+    let input: String = data_url.serialize(Some(true));
+    let input: &str = &input["data:".len()..];
+
+    let (mime_type, encoded_body) = match input.split_once(',') {
+        Some(parts) => parts,
+        None => return Err(DataUrlError::MissingComma),
+    };
+
+    let mut mime_type: String = mime_type.trim_matches(|c: char| c.is_ascii_whitespace()).to_string();
+    let mut body: Vec<u8> = percent_decode_string(encoded_body);
+
+    if ends_with_base64_marker(&mime_type) {
+        // Isomorphic decode.
+        let string_body: String = body.iter().map(|byte| *byte as char).collect();
+
+        body = match forgiving_base64_decode(&string_body) {
+            Some(decoded) => decoded,
+            None => return Err(DataUrlError::InvalidBase64),
+        };
+
+        mime_type.truncate(mime_type.len() - 6);
+        mime_type.truncate(mime_type.trim_end_matches(' ').len());
+        mime_type.pop();
+    }
+
+    if mime_type.starts_with(';') {
+        mime_type.insert_str(0, "text/plain");
+    }
+
+    let mime_type_record: MimeType = MimeType::parse(&mime_type).unwrap_or(MimeType {
+        type_: "text".to_string(),
+        subtype: "plain".to_string(),
+        parameters: vec![("charset".to_string(), "US-ASCII".to_string())],
+    });
+
+    Ok((mime_type_record, body))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::types::ParseOptions;
+    use crate::urls::basic_url_parser;
+
+    fn process(input: &str) -> Result<(String, Vec<u8>), DataUrlError> {
+        let url = basic_url_parser(input.to_string(), None, None, None, &ParseOptions::default()).unwrap();
+        data_url_processor(&url).map(|(mime_type, body)| (mime_type.to_string(), body))
+    }
+
+    #[test]
+    fn test_parse_mime_type() {
+        let mime_type = MimeType::parse(" Text/HTML ; Charset=\"utf-8\" ;;foo=bar;foo=baz; empty=").unwrap();
+        assert_eq!(mime_type.essence(), "text/html");
+        assert_eq!(mime_type.get_parameter("charset"), Some("utf-8"));
+        assert_eq!(mime_type.get_parameter("foo"), Some("bar"));
+        assert_eq!(mime_type.get_parameter("empty"), None);
+        assert_eq!(mime_type.to_string(), "text/html;charset=utf-8;foo=bar");
+
+        assert_eq!(MimeType::parse("text/plain;x=\"a\\\"b\"").unwrap().to_string(), "text/plain;x=\"a\\\"b\"");
+        assert_eq!(MimeType::parse("text"), None);
+        assert_eq!(MimeType::parse("text/"), None);
+        assert_eq!(MimeType::parse("te xt/plain"), None);
+    }
+
+    #[test]
+    fn test_forgiving_base64_decode() {
+        assert_eq!(forgiving_base64_decode("aGVsbG8="), Some(b"hello".to_vec()));
+        assert_eq!(forgiving_base64_decode(" aGVs bG8 "), Some(b"hello".to_vec()));
+        assert_eq!(forgiving_base64_decode("YQ"), Some(b"a".to_vec()));
+        assert_eq!(forgiving_base64_decode("YQ=="), Some(b"a".to_vec()));
+        assert_eq!(forgiving_base64_decode("YQ==="), None);
+        assert_eq!(forgiving_base64_decode("Y"), None);
+        assert_eq!(forgiving_base64_decode("Y!=="), None);
+    }
+
+    #[test]
+    fn test_data_url_processor() {
+        assert_eq!(process("data:,Hello%2C%20World%21"), Ok(("text/plain;charset=US-ASCII".to_string(), b"Hello, World!".to_vec())));
+        assert_eq!(process("data:text/html;charset=utf-8,<p>hi</p>#frag"), Ok(("text/html;charset=utf-8".to_string(), b"<p>hi</p>".to_vec())));
+        assert_eq!(process("data:image/png;base64,iVBORw0K"), Ok(("image/png".to_string(), vec![0x89, b'P', b'N', b'G', b'\r', b'\n'])));
+        assert_eq!(process("data:;BASE64,YQ"), Ok(("text/plain;charset=US-ASCII".to_string(), b"a".to_vec())));
+        assert_eq!(process("data:text/plain ;  base64,YQ"), Ok(("text/plain".to_string(), b"a".to_vec())));
+        assert_eq!(process("data:text/plain;base 64,YQ"), Ok(("text/plain".to_string(), b"YQ".to_vec())));
+        assert_eq!(process("data:,a?b"), Ok(("text/plain;charset=US-ASCII".to_string(), b"a?b".to_vec())));
+        assert_eq!(process("data:;base64,%"), Err(DataUrlError::InvalidBase64));
+        assert_eq!(process("data:text/plain"), Err(DataUrlError::MissingComma));
+        assert_eq!(process("about:blank"), Err(DataUrlError::NotDataScheme));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::types::types::Diagnostic;
+
+// C0 controls and DEL are shown as their Control Pictures, so that a caret under them lines up
+// and a newline does not break the rendered input in two.
+fn visible(c: char) -> char {
+    match c {
+        '\u{0}'..='\u{1F}' => char::from_u32(0x2400 + c as u32).unwrap(),
+        '\u{7F}' => '\u{2421}',
+        _ => c,
+    }
+}
+
+impl Diagnostic {
+    // Renders the error for a terminal, with carets under the code points of `input` it applies
+    // to. `input` must be the string that was parsed. For example:
+    //
+    // port-out-of-range: Port is too big!
+    // http://example.com:99999/
+    //                    ^^^^^
+    pub fn render(&self, input: &str) -> String {
+        let start: usize = self.span.start.min(input.len());
+        let end: usize = self.span.end.clamp(start, input.len());
+
+        let column: usize = input.char_indices().take_while(|(offset, _)| *offset < start).count();
+        let width: usize = input.char_indices().filter(|(offset, _)| (start..end).contains(offset)).count();
+
+        let mut output: String = format!("{}: {}\n", self.error.name(), self.error);
+        output.extend(input.chars().map(visible));
+        output += "\n";
+        output += &" ".repeat(column);
+        output += &"^".repeat(width.max(1));
+
+        output
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{HostError, ReslocError, UrlError, ValidationError};
+    use crate::types::types::{ParseOptions, URL};
+
+    fn render(input: &str) -> String {
+        let error: ReslocError = URL::parse(input).err().unwrap();
+        error.diagnostic().unwrap().render(input)
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render("http://example.com:99999/"),
+            "port-out-of-range: Port is too big!\nhttp://example.com:99999/\n                   ^^^^^"
+        );
+        assert_eq!(
+            render("http://[:1]/"),
+            format!("IPv6-invalid-compression: {}\nhttp://[:1]/\n       ^^^^", HostError::Ipv6InvalidCompression)
+        );
+
+        // The span is in bytes, but the carets line up with code points.
+        assert_eq!(
+            render("http://b\u{FC}cher\u{E9}:x/"),
+            format!("port-invalid: {}\nhttp://b\u{FC}cher\u{E9}:x/\n               ^", UrlError::PortInvalid)
+        );
+
+        // An empty span points between two code points.
+        let diagnostic = Diagnostic { error: ValidationError::Url(UrlError::SSMissingFollowingSolidus), span: 5..5 };
+        assert!(diagnostic.render("http:example.com").ends_with("\nhttp:example.com\n     ^"));
+    }
+
+    #[test]
+    fn test_parse_with_diagnostics() {
+        let input = "\thttp://user@exa\nmple.com\\a";
+        let (url, diagnostics) = URL::parse_with_diagnostics(input, None, &ParseOptions::default());
+        assert_eq!(url.unwrap().serialize(None), "http://user@example.com/a");
+
+        let found: Vec<(&str, &str)> = diagnostics.iter().map(|diagnostic| (diagnostic.error.name(), &input[diagnostic.span.clone()])).collect();
+        assert_eq!(found, vec![("invalid-URL-unit", "\t"), ("invalid-URL-unit", "\n"), ("invalid-credentials", "user@"), ("invalid-reverse-solidus", "\\")]);
+
+        // Control characters are rendered as visible symbols.
+        assert!(diagnostics[1].render(input).contains("\u{2409}http://user@exa\u{240A}mple.com\\a\n                ^"));
+
+        let (url, diagnostics) = URL::parse_with_diagnostics("http://exa mple.com/", None, &ParseOptions::default());
+        assert_eq!(url.err().unwrap().diagnostic().as_ref(), diagnostics.last());
+        assert_eq!(diagnostics.last().unwrap().span, 7..19);
+
+        // Spans stay byte ranges of the input across multibyte code points and removed tabs.
+        let input = "http://\u{E9}\u{E9}\t.com/\u{1F980}%zz\t\u{1F980}\\b";
+        let (url, diagnostics) = URL::parse_with_diagnostics(input, None, &ParseOptions::default());
+        assert_eq!(url.unwrap().serialize(None), "http://xn--9caa.com/%F0%9F%A6%80%zz%F0%9F%A6%80/b");
+
+        let found: Vec<(&str, &str)> = diagnostics.iter().map(|diagnostic| (diagnostic.error.name(), &input[diagnostic.span.clone()])).collect();
+        assert_eq!(found, vec![("invalid-URL-unit", "\t"), ("invalid-URL-unit", "\t"), ("invalid-URL-unit", "%"), ("invalid-reverse-solidus", "\\")]);
+
+        let input = "http://\u{E9}\t:99999/";
+        let (_, diagnostics) = URL::parse_with_diagnostics(input, None, &ParseOptions::default());
+        assert_eq!(&input[diagnostics.last().unwrap().span.clone()], "99999");
+    }
+}
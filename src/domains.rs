@@ -1,10 +1,12 @@
-use std::collections::HashMap;
-
 use crate::errors::{IDNAError, HostError};
-use crate::types::types::{Ipv4NumberResult, Ipv6Pieces, IPv4};
+use crate::types::types::{Ipv4NumberResult, Ipv6Pieces, IPv4, ParseOptions};
 
-use idna::{Config, Errors};
+use idna::{punycode, Config, Errors, Idna};
+use unicode_joining_type::{get_joining_type, JoiningType};
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::UnicodeNormalization;
 
+// Like `domain_to_ascii_with_options`, with every other option left at its default.
 pub fn unicode_to_ascii(
     domain: String,
     check_hyphens: bool,
@@ -12,27 +14,133 @@ pub fn unicode_to_ascii(
     transitional_processing: bool,
     verify_dns_length: bool) ->  Result<String, IDNAError> {
 
-    Config::default()
-        .use_std3_ascii_rules(use_std3_ascii_rules)
+    let options: ParseOptions = ParseOptions::default()
         .check_hyphens(check_hyphens)
+        .use_std3_ascii_rules(use_std3_ascii_rules)
         .transitional_processing(transitional_processing)
-        .verify_dns_length(verify_dns_length)
-        .to_ascii(&domain)
-        .map_err(|_| {
-            IDNAError::DomainToAscii
-        })
+        .verify_dns_length(verify_dns_length);
+
+    domain_to_ascii_with_options(domain, &options)
 }
 
-pub fn domain_to_ascii(domain: String, be_strict: bool) -> Result<String, IDNAError> {
-    let result: Result<String, IDNAError> = unicode_to_ascii(domain, be_strict, false, false, be_strict);
+// idna 0.3 has no way to turn off its bidi rule, and only tells which checks failed through
+// the Display output of `Errors`, e.g. "Errors { check_bidi, too_long_for_dns }". The version is
+// pinned in Cargo.toml, and test_failed_idna_checks breaks if the format changes.
+fn failed_idna_checks(errors: &Errors) -> Vec<String> {
+    let errors: String = errors.to_string();
+    errors
+        .trim_start_matches("Errors {")
+        .trim_end_matches('}')
+        .split(',')
+        .map(str::trim)
+        .filter(|check| !check.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// Reference: https://www.rfc-editor.org/rfc/rfc5892#appendix-A.1
+// Reference: https://www.rfc-editor.org/rfc/rfc5892#appendix-A.2
+// A ZERO WIDTH JOINER is only allowed after a virama. A ZERO WIDTH NON-JOINER is also allowed
+// between a left- or dual-joining character and a right- or dual-joining one, skipping over
+// transparent characters.
+fn passes_joiners(label: &str) -> bool {
+    const VIRAMA: u8 = 9;
+    let label: Vec<char> = label.chars().collect();
+
+    for (index, c) in label.iter().enumerate() {
+        if *c != '\u{200C}' && *c != '\u{200D}' {
+            continue;
+        }
+
+        if index > 0 && canonical_combining_class(label[index - 1]) == VIRAMA {
+            continue;
+        }
+
+        if *c == '\u{200D}' {
+            return false;
+        }
+
+        let is_not_transparent = |joining_type: &JoiningType| *joining_type != JoiningType::Transparent;
+        let before = label[..index].iter().rev().map(|c| get_joining_type(*c)).find(is_not_transparent);
+        let after = label[index + 1..].iter().map(|c| get_joining_type(*c)).find(is_not_transparent);
+
+        if !matches!(before, Some(JoiningType::LeftJoining | JoiningType::DualJoining))
+            || !matches!(after, Some(JoiningType::RightJoining | JoiningType::DualJoining)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+// Reference: https://www.unicode.org/reports/tr46/#ProcessingStepPunycode
+// Reference: https://www.unicode.org/reports/tr46/#Validity_Criteria
+// idna 0.3 predates two rules added in Unicode 15.1: an A-label may not decode to an empty or
+// all-ASCII label, and a decoded label may not start with "xn--". Without them, hosts like
+// "xn--xn--xn--" serialize differently each time they are parsed.
+fn is_invalid_a_label(label: &str) -> bool {
+    let encoded: &str = match label.strip_prefix("xn--") {
+        Some(encoded) => encoded,
+        None => return false,
+    };
+
+    match punycode::decode_to_string(encoded) {
+        Some(decoded) => decoded.is_empty() || decoded.is_ascii() || decoded.starts_with("xn--"),
+        None => true,
+    }
+}
+
+fn has_valid_dns_length(domain: &str) -> bool {
+    let domain: &str = domain.strip_suffix('.').unwrap_or(domain);
+    !domain.is_empty()
+        && domain.len() <= 253
+        && domain.split('.').all(|label| !label.is_empty() && label.len() <= 63)
+}
+
+// Reference: https://url.spec.whatwg.org/#concept-domain-to-ascii
+// Reference: https://www.unicode.org/reports/tr46/#ToASCII
+pub fn domain_to_ascii_with_options(domain: String, options: &ParseOptions) -> Result<String, IDNAError> {
+    let config: Config = Config::default()
+        .use_std3_ascii_rules(options.use_std3_ascii_rules)
+        .check_hyphens(options.check_hyphens)
+        .transitional_processing(options.transitional_processing);
+
+    let mut result: String = String::new();
+    let errors: Errors = Idna::new(config).to_ascii_inner(&domain, &mut result);
+
+    if failed_idna_checks(&errors).iter().any(|check| options.check_bidi || check != "check_bidi") {
+        return Err(IDNAError::DomainToAscii);
+    }
+
+    let labels = domain.split(['.', '\u{3002}', '\u{FF0E}', '\u{FF61}']).chain(result.split('.'));
+    if labels.map(|label| label.nfkc().collect::<String>().to_lowercase()).any(|label| is_invalid_a_label(&label)) {
+        return Err(IDNAError::DomainToAscii);
+    }
+
+    if options.verify_dns_length && !has_valid_dns_length(&result) {
+        return Err(IDNAError::DomainToAscii);
+    }
+
+    if options.check_joiners {
+        let passes: bool = result
+            .split('.')
+            .filter_map(|label| label.strip_prefix("xn--"))
+            .all(|label| punycode::decode_to_string(label).is_some_and(|label| passes_joiners(&label)));
 
-    if let Ok(ref domain) = result {
-        if domain.is_empty() {
+        if !passes {
             return Err(IDNAError::DomainToAscii);
         }
     }
 
-    return result;
+    if result.is_empty() {
+        return Err(IDNAError::DomainToAscii);
+    }
+
+    Ok(result)
+}
+
+pub fn domain_to_ascii(domain: String, be_strict: bool) -> Result<String, IDNAError> {
+    domain_to_ascii_with_options(domain, &ParseOptions::default().be_strict(be_strict))
 }
 
 fn unicode_to_unicode(
@@ -49,35 +157,33 @@ fn unicode_to_unicode(
 }
 
 
-pub fn domain_to_unicode(domain: String, be_strict: bool) -> String {
+// Reference: https://url.spec.whatwg.org/#concept-domain-to-unicode
+// The result is returned even when there are errors, which are validation errors only.
+pub fn domain_to_unicode(domain: String, be_strict: bool) -> (String, Result<(), IDNAError>) {
     let (result, errors) = unicode_to_unicode(domain, false, be_strict, false);
-    if errors.is_err() {
-        println!("Unicode to ASCII gave following errors!: {}", errors.unwrap_err());
-    } 
-
-    return result;
+    (result, errors.map_err(|_| IDNAError::DomainToUnicode))
 }
 
 pub fn parse_ipv4_number(input: &str) -> Ipv4NumberResult {
     let mut input_chars = input.chars().collect::<Vec<char>>();
 
-    if input_chars.len() == 0 {
+    if input_chars.is_empty() {
         return Err(HostError::Ipv4Failure);
     }
 
     let mut validation_error: bool = false;
     let mut radix: u32 = 10;
 
-    if input_chars.len() > 2 {
+    if input_chars.len() >= 2 {
         match input_chars[0..2] {
             ['0', 'x'] | ['0', 'X'] => {
                 validation_error = true;
-                input_chars = input_chars[0..2].to_vec();
+                input_chars = input_chars[2..].to_vec();
                 radix = 16;
             }
-            ['0'] => {
+            ['0', _] => {
                 validation_error = true;
-                input_chars = input_chars[0..1].to_vec();
+                input_chars = input_chars[1..].to_vec();
                 radix = 8;
             }
 
@@ -85,29 +191,36 @@ pub fn parse_ipv4_number(input: &str) -> Ipv4NumberResult {
         }
     }
 
-    if input_chars.len() == 0 {
+    if input_chars.is_empty() {
         return Ok((0, true));
     }
 
+    let mut result: u64 = 0;
     for point in input_chars {
-        if !point.is_digit(radix) {
-            return Err(HostError::Ipv4Failure);
-        }
-    }
+        let digit = match point.to_digit(radix) {
+            Some(digit) => digit,
+            None => return Err(HostError::Ipv4Failure),
+        };
 
-    let result: u8 = input
-        .parse::<u8>()
-        .expect("Number is not parsable!");
+        // Anything past u32::MAX is out of range either way, so saturate instead of overflowing.
+        result = result.saturating_mul(radix as u64).saturating_add(digit as u64);
+    }
 
     Ok((result, validation_error))
 }
 
 pub fn ipv4_parser(input: String) -> Result<IPv4, HostError> {
+    ipv4_parser_with_validation(&input, &mut Vec::new())
+}
+
+// Reference: https://url.spec.whatwg.org/#concept-ipv4-parser
+// Validation errors that do not cause a failure are pushed to `validation_errors`.
+pub(crate) fn ipv4_parser_with_validation(input: &str, validation_errors: &mut Vec<HostError>) -> Result<IPv4, HostError> {
     let mut parts: Vec<&str> = input.split(".").collect();
 
     if let Some(last) = parts.last() {
         if last.is_empty() {
-            println!("{}", HostError::Ipv4EmptyPart);
+            validation_errors.push(HostError::Ipv4EmptyPart);
             if parts.len() > 1 {
                 parts.pop();
             }
@@ -115,54 +228,51 @@ pub fn ipv4_parser(input: String) -> Result<IPv4, HostError> {
     }
 
     if parts.len() > 4 {
-        println!("{}", HostError::Ipv4TooManyParts);
         return Err(HostError::Ipv4TooManyParts);
     }
 
-    let mut numbers: Vec<u32> = Vec::with_capacity(4);
+    let mut numbers: Vec<u64> = Vec::with_capacity(4);
 
     for part in parts {
         let result: Ipv4NumberResult  = parse_ipv4_number(part);
 
-
         match result {
-            Err(_) => {
-                eprintln!("{}", HostError::Ipv4NonNumericPart);
-                return Err(HostError::Ipv4NonNumericPart);
-            },
-            Ok(num) => {
-                match num.1 {
-                    true => eprintln!("{}", HostError::Ipv4OutOfRangePart),
-                    false => numbers.push(num.0.into()),
+            Err(_) => return Err(HostError::Ipv4NonNumericPart),
+            Ok((number, validation_error)) => {
+                if validation_error {
+                    validation_errors.push(HostError::Ipv4NonDecimalPart);
                 }
+                numbers.push(number);
             }
         }
     }
 
-
     for (idx, num) in numbers.iter().enumerate() {
-        if num > &255 {
-            eprintln!("{}", HostError::Ipv4OutOfRangePart);
+        if *num > 255 {
             if idx != numbers.len() - 1 {
-                return Err(HostError::Ipv4Failure);
-            } else {
-                if num > &256_u32.pow(5 - numbers.len() as u32) {
-                    return Err(HostError::Ipv4Failure);
-                }
+                return Err(HostError::Ipv4OutOfRangePart);
             }
+            validation_errors.push(HostError::Ipv4OutOfRangePart);
         }
     }
 
+    // The URL Standard does not report shorthands such as "127.1", but they are serialized in
+    // their expanded form, so strict mode should reject them too.
+    if numbers.len() < 4 {
+        validation_errors.push(HostError::Ipv4TooFewParts);
+    }
+
     let mut ipv4 = numbers.pop().unwrap();
 
-    let mut counter: u32 = 0;
-    for n in numbers {
-        ipv4 = ipv4 + (n * 256_u32.pow(3 - counter));
-        // TODO: Replace counter with enumerate's index.
-        counter += 1;
+    if ipv4 >= 256_u64.pow(4 - numbers.len() as u32) {
+        return Err(HostError::Ipv4OutOfRangePart);
+    }
+
+    for (counter, n) in numbers.iter().enumerate() {
+        ipv4 += n * 256_u64.pow(3 - counter as u32);
     }
 
-    Ok(ipv4)
+    Ok(ipv4 as IPv4)
 }
 
 
@@ -171,47 +281,34 @@ pub fn ipv4_serializer(address: u32) -> String {
     let mut n = address;
 
     for i in 1..=4 {
-        output += &(n % 256).to_string();
+        output.insert_str(0, &(n % 256).to_string());
         if i != 4 {
-            output += ".";
+            output.insert(0, '.');
         }
 
         n = (n as f64 / 256_f64).floor() as u32;
     }
 
-    return output;
+    output
 }
 
+// Reference: https://url.spec.whatwg.org/#concept-ipv6-serializer
+// The start of the first longest run of two or more 0 pieces, if there is one.
 fn get_first_longest_sequence(address: &Ipv6Pieces) -> Option<usize> {
-    let mut sequence_map: HashMap<usize, usize> = HashMap::new();
+    let mut longest: Option<(usize, usize)> = None;
+    let mut index: usize = 0;
 
-    let mut count: usize = 0;
-    let mut s_idx: usize = 0;
+    while index < address.len() {
+        let length: usize = address[index..].iter().take_while(|piece| **piece == 0).count();
 
-    for (idx, piece) in address.iter().enumerate() {
-        if *piece == 0 {
-            if count == 0 {
-                s_idx = idx;
-            }
-            count += 1;
-        } else {
-            sequence_map.insert(count, s_idx);
-            count = 0;
+        if length > 1 && longest.is_none_or(|(_, longest_length)| length > longest_length) {
+            longest = Some((index, length));
         }
-    }
-
-    // In case if all the elements are 0, then we need to 'flush' it.
-    sequence_map.insert(count, s_idx);
 
-    if let Some(x) = sequence_map.keys().max() {
-        if *x == 1 || *x == 0 {
-            None
-        } else {
-            sequence_map.get(x).cloned()
-        }
-    } else {
-        panic!("Failed to get map entry!");
+        index += length.max(1);
     }
+
+    longest.map(|(start, _)| start)
 }
 
 pub fn ipv6_serializer(address: Ipv6Pieces) -> String {
@@ -224,7 +321,7 @@ pub fn ipv6_serializer(address: Ipv6Pieces) -> String {
             continue;
         }
 
-        if ignore0 == true {
+        if ignore0 {
             ignore0 = false;
         }
 
@@ -252,18 +349,141 @@ pub fn ipv6_serializer(address: Ipv6Pieces) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+    use crate::hosts::ipv6_parser;
     use crate::types::types::Ipv6Address;
 
+    #[test]
+    fn test_domain_to_ascii_options() {
+        let to_ascii = |domain: &str, options: ParseOptions| domain_to_ascii_with_options(domain.to_string(), &options).ok();
+        let default = ParseOptions::default();
+
+        assert_eq!(to_ascii("B\u{FC}cher.de", default), Some("xn--bcher-kva.de".to_string()));
+        assert_eq!(to_ascii("", default), None);
+
+        assert_eq!(to_ascii("a-.com", default), Some("a-.com".to_string()));
+        assert_eq!(to_ascii("a-.com", default.be_strict(true)), None);
+        assert_eq!(to_ascii("a-.com", default.check_hyphens(true)), None);
+
+        assert_eq!(to_ascii("a_b.com", default), Some("a_b.com".to_string()));
+        assert_eq!(to_ascii("a_b.com", default.use_std3_ascii_rules(true)), None);
+
+        assert_eq!(to_ascii("fa\u{DF}.de", default), Some("xn--fa-hia.de".to_string()));
+        assert_eq!(to_ascii("fa\u{DF}.de", default.transitional_processing(true)), Some("fass.de".to_string()));
+
+        let long_label: String = "a".repeat(64);
+        assert_eq!(to_ascii(&long_label, default), Some(long_label.clone()));
+        assert_eq!(to_ascii(&long_label, default.verify_dns_length(true)), None);
+        assert_eq!(to_ascii("a..b", default.verify_dns_length(true)), None);
+        assert_eq!(to_ascii("example.com.", default.be_strict(true)), Some("example.com.".to_string()));
+
+        // An RTL label may not start with a digit.
+        assert_eq!(to_ascii("0\u{05D0}.com", default), None);
+        assert!(to_ascii("0\u{05D0}.com", default.check_bidi(false)).is_some());
+
+        assert_eq!(to_ascii("a\u{200D}b.com", default), None);
+        assert!(to_ascii("a\u{200D}b.com", default.check_joiners(false)).is_some());
+        assert_eq!(to_ascii("a\u{200C}b.com", default), None);
+        assert!(to_ascii("\u{0915}\u{094D}\u{200D}\u{0937}.com", default).is_some());
+        assert!(to_ascii("\u{0645}\u{200C}\u{0627}.com", default).is_some());
+    }
+
+    // The same checks apply as through `domain_to_ascii_with_options`.
+    #[test]
+    fn test_unicode_to_ascii() {
+        assert_eq!(unicode_to_ascii("B\u{FC}cher.de".to_string(), false, false, false, false).ok(), Some("xn--bcher-kva.de".to_string()));
+        assert!(unicode_to_ascii("a-.com".to_string(), true, false, false, false).is_err());
+        assert!(unicode_to_ascii("0\u{05D0}.com".to_string(), false, false, false, false).is_err());
+        assert!(unicode_to_ascii("a\u{200D}b.com".to_string(), false, false, false, false).is_err());
+        assert!(unicode_to_ascii("xn--abc.com".to_string(), false, false, false, false).is_err());
+        assert!(unicode_to_ascii("a".repeat(64), false, false, false, true).is_err());
+    }
+
+    #[test]
+    fn test_failed_idna_checks() {
+        let checks = |domain: &str, config: Config| -> Vec<String> {
+            let mut result: String = String::new();
+            failed_idna_checks(&Idna::new(config).to_ascii_inner(domain, &mut result))
+        };
+
+        assert!(checks("example.com", Config::default()).is_empty());
+        assert_eq!(checks("0\u{05D0}.com", Config::default()), ["check_bidi"]);
+        assert_eq!(checks("a-.com", Config::default().check_hyphens(true)), ["check_hyphens"]);
+        assert_eq!(
+            checks("a-.b_c.com", Config::default().check_hyphens(true).use_std3_ascii_rules(true)),
+            ["check_hyphens", "invalid_mapping", "disallowed_by_std3_ascii_rules"]
+        );
+    }
+
+    #[test]
+    fn test_domain_to_unicode() {
+        let (result, errors) = domain_to_unicode("xn--bcher-kva.de".to_string(), false);
+        assert_eq!(result, "b\u{FC}cher.de");
+        assert!(errors.is_ok());
+
+        let (result, errors) = domain_to_unicode("xn--a.xn--bcher-kva.de".to_string(), false);
+        assert!(result.ends_with(".b\u{FC}cher.de"));
+        assert!(matches!(errors, Err(IDNAError::DomainToUnicode)));
+
+        let (result, errors) = domain_to_unicode("a_b.com".to_string(), true);
+        assert_eq!(result, "a_b.com");
+        assert!(errors.is_err());
+    }
+
+    #[test]
+    fn test_invalid_a_labels() {
+        assert!(!is_invalid_a_label("example"));
+        assert!(!is_invalid_a_label("xn--bcher-kva"));
+        assert!(is_invalid_a_label("xn--"));
+        assert!(is_invalid_a_label("xn--abc-"));
+        assert!(is_invalid_a_label("xn--xn--ab-gva"));
+
+        let to_ascii = |domain: &str| domain_to_ascii_with_options(domain.to_string(), &ParseOptions::default()).ok();
+
+        // A-labels that decode to nothing or to plain ASCII, in the input or in the result.
+        assert_eq!(to_ascii("xn--xn--xn--"), None);
+        assert_eq!(to_ascii("a.xn--abc-"), None);
+        assert_eq!(to_ascii("xn--"), None);
+        assert_eq!(to_ascii("\u{FF58}\u{FF4E}--abc-"), None);
+        assert_eq!(to_ascii("xn--abc-\u{3002}com"), None);
+        assert_eq!(to_ascii("xn--xn--ab-gva"), None);
+        assert_eq!(to_ascii("XN--BCHER-KVA.de"), Some("xn--bcher-kva.de".to_string()));
+    }
+
     #[test]
     fn test_u128_to_u16_array() {
         let ipv6_address = Ipv6Address(0xabcdefabcdefabcdefabcdefabcdefab);
         assert_eq!(Ipv6Pieces::from(ipv6_address), [0xefab, 0xabcd, 0xcdef, 0xefab, 0xabcd, 0xcdef, 0xefab, 0xabcd]);
     }
 
+    #[test]
+    fn test_ipv4_parser() {
+        assert_eq!(ipv4_parser("127.0.0.1".to_string()).unwrap(), 0x7F000001);
+        assert_eq!(ipv4_parser("0x7f.1".to_string()).unwrap(), 0x7F000001);
+        assert_eq!(ipv4_parser("017700000001".to_string()).unwrap(), 0x7F000001);
+        assert_eq!(ipv4_parser("4294967295".to_string()).unwrap(), u32::MAX);
+        assert_eq!(ipv4_parser("1.2.3.4.".to_string()).unwrap(), 0x01020304);
+        assert!(ipv4_parser("4294967296".to_string()).is_err());
+        assert!(ipv4_parser("256.0.0.1".to_string()).is_err());
+        assert!(ipv4_parser("1.2.3.4.5".to_string()).is_err());
+        assert!(ipv4_parser("1.2.3.09".to_string()).is_err());
+
+        let validation_errors = |input: &str| {
+            let mut validation_errors: Vec<HostError> = Vec::new();
+            ipv4_parser_with_validation(input, &mut validation_errors).unwrap();
+            validation_errors
+        };
+        assert_eq!(validation_errors("127.0.0.1"), vec![]);
+        assert_eq!(validation_errors("127.1"), vec![HostError::Ipv4TooFewParts]);
+        assert_eq!(validation_errors("0x7f.0.0.1."), vec![HostError::Ipv4EmptyPart, HostError::Ipv4NonDecimalPart]);
+        assert_eq!(validation_errors("2130706433"), vec![HostError::Ipv4OutOfRangePart, HostError::Ipv4TooFewParts]);
+    }
+
     #[test]
     fn test_ipv4_serializer() {
-        let ipv4_address = u32::max_value();
+        let ipv4_address = u32::MAX;
         assert_eq!(ipv4_serializer(ipv4_address), "255.255.255.255".to_string());
+        assert_eq!(ipv4_serializer(0x7F000001), "127.0.0.1".to_string());
     }
 
     #[test]
@@ -277,5 +497,55 @@ mod tests {
     #[test]
     fn test_longest_sequence() {
         assert_eq!(get_first_longest_sequence(&[0x0,0xf,0x0,0x0,0xf,0xf,0xf,0xf]), Some(2));
+        assert_eq!(get_first_longest_sequence(&[0x1,0x0,0x0,0x1,0x0,0x0,0x1,0x1]), Some(1));
+        assert_eq!(get_first_longest_sequence(&[0x1,0x0,0x1,0x0,0x1,0x0,0x1,0x0]), None);
+        assert_eq!(ipv6_serializer([0x1, 0, 0, 0, 0, 0, 0, 0]), "1::");
+        assert_eq!(ipv6_serializer([0x2001, 0xdb8, 0, 0, 0x1, 0, 0, 0x1]), "2001:db8::1:0:0:1");
+    }
+
+    // Half of the pieces are zero, so that runs of zero pieces of every length come up.
+    fn ipv6_pieces_strategy() -> impl Strategy<Value = Ipv6Pieces> {
+        prop::array::uniform8(prop_oneof![Just(0u16), any::<u16>()])
+    }
+
+    proptest! {
+        #[test]
+        fn test_ipv4_round_trip(address in any::<u32>()) {
+            let serialized = ipv4_serializer(address);
+            prop_assert_eq!(ipv4_parser(serialized.clone()), Ok(address), "{}", serialized);
+        }
+
+        #[test]
+        fn test_ipv6_round_trip(address in any::<u128>()) {
+            let pieces: Ipv6Pieces = Ipv6Address(address).into();
+            let serialized = ipv6_serializer(pieces);
+            prop_assert_eq!(ipv6_parser(serialized.clone()), Ok(pieces), "{}", serialized);
+        }
+
+        #[test]
+        fn test_ipv6_compression(pieces in ipv6_pieces_strategy()) {
+            let serialized = ipv6_serializer(pieces);
+            prop_assert_eq!(ipv6_parser(serialized.clone()), Ok(pieces), "{}", serialized);
+
+            // The first of the longest runs of at least two zero pieces, found by brute force.
+            let mut expected: Option<(usize, usize)> = None;
+            for start in 0..8 {
+                let length = pieces[start..].iter().take_while(|piece| **piece == 0).count();
+                if length >= 2 && expected.is_none_or(|(_, longest)| length > longest) {
+                    expected = Some((start, length));
+                }
+            }
+
+            // Where "::" is and how many pieces are written on either side of it tells which
+            // run it stands for.
+            let compressed = serialized.split_once("::").map(|(before, after)| {
+                let count = |pieces: &str| pieces.split(':').filter(|piece| !piece.is_empty()).count();
+                (count(before), 8 - count(before) - count(after))
+            });
+
+            prop_assert_eq!(compressed, expected, "{}", serialized);
+            prop_assert!(serialized.matches("::").count() <= 1);
+            prop_assert!(serialized.split(':').all(|piece| piece.len() <= 4 && (piece.len() <= 1 || !piece.starts_with('0'))));
+        }
     }
 }
@@ -1,29 +1,85 @@
-use std::fmt::{self, write};
+use std::fmt;
+use std::ops::Range;
 
-#[derive(Debug)]
+use crate::types::types::Diagnostic;
+
+#[derive(Debug, PartialEq)]
 pub enum ReslocError {
+    // A failure the URL Standard reports without a validation error, e.g. when a state override
+    // would change a special scheme into a non-special one.
     Failure,
+    // The validation error that made parsing fail, and the byte range of the input it applies to.
+    ValidationError { error: ValidationError, span: Range<usize> },
+    // The input went over one of the resource limits in `ParseOptions`.
+    LimitExceeded(LimitError),
 }
 
-#[derive(Debug)]
-pub enum IDNAError {
-    DomainToAscii,
-    DomainToUnicode,
+#[derive(Clone, Debug, PartialEq)]
+pub enum LimitError {
+    InputLength,
+    PathSegments,
+    HostLength,
+    QueryLength,
+    SearchParams,
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputLength => write!(f, "Input is longer than the limit!"),
+            Self::PathSegments => write!(f, "Path has more segments than the limit!"),
+            Self::HostLength => write!(f, "Host is longer than the limit!"),
+            Self::QueryLength => write!(f, "Query is longer than the limit!"),
+            Self::SearchParams => write!(f, "Query has more search params than the limit!"),
+        }
+    }
 }
 
-impl IDNAError {
-    fn should_fail(self: Self) -> bool {
+impl ReslocError {
+    pub fn diagnostic(&self) -> Option<Diagnostic> {
         match self {
-            Self::DomainToAscii => true,
-            Self::DomainToUnicode => false,
+            Self::Failure | Self::LimitExceeded(_) => None,
+            Self::ValidationError { error, span } => Some(Diagnostic { error: error.clone(), span: span.clone() }),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    Url(UrlError),
+    Host(HostError),
+}
+
+impl ValidationError {
+    // Reference: https://url.spec.whatwg.org/#validation-error
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Url(error) => error.name(),
+            Self::Host(error) => error.name(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Url(error) => write!(f, "{}", error),
+            Self::Host(error) => write!(f, "{}", error),
+        }
+    }
+}
 
 #[derive(Debug)]
+pub enum IDNAError {
+    DomainToAscii,
+    DomainToUnicode,
+}
+
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum HostError {
     Ipv4Failure,
+    DomainToAscii,
     DomainInvalidCodePoint,
     HostInvalidCodePoint,
     Ipv4EmptyPart,
@@ -31,6 +87,7 @@ pub enum HostError {
     Ipv4NonNumericPart,
     Ipv4NonDecimalPart,
     Ipv4OutOfRangePart,
+    Ipv4TooFewParts,
     Ipv6Unclosed,
     Ipv6InvalidCompression,
     Ipv6MultipleCompression,
@@ -44,26 +101,31 @@ pub enum HostError {
 }
 
 impl HostError {
-    fn should_fail(self: Self) -> bool {
+    // Reference: https://url.spec.whatwg.org/#validation-error
+    // Ipv4Failure is only used inside the IPv4 parser, which reports it as a non-numeric part,
+    // and Ipv4TooFewParts is not in the URL Standard.
+    pub fn name(&self) -> &'static str {
         match self {
-            Self::Ipv4Failure => true,
-            Self::DomainInvalidCodePoint => true,
-            Self::HostInvalidCodePoint => true,
-            Self::Ipv4EmptyPart => false,
-            Self::Ipv4TooManyParts => true,
-            Self::Ipv4NonNumericPart => true,
-            Self::Ipv4NonDecimalPart => false,
-            Self::Ipv4OutOfRangePart => todo!(),
-            Self::Ipv6Unclosed => true, 
-            Self::Ipv6InvalidCompression => true,
-            Self::Ipv6MultipleCompression => true,
-            Self::Ipv6TooManyPieces => true,
-            Self::Ipv6InvalidCodePoint => true,
-            Self::Ipv6TooFewPieces => true,
-            Self::Ipv4InIpv6TooManyPieces => true,
-            Self::Ipv4InIpv6InvalidCodePoint => true,
-            Self::Ipv4InIpv6OutOfRangePart => true,
-            Self::Ipv4InIpv6TooFewParts => true,
+            Self::Ipv4Failure => "IPv4-non-numeric-part",
+            Self::DomainToAscii => "domain-to-ASCII",
+            Self::DomainInvalidCodePoint => "domain-invalid-code-point",
+            Self::HostInvalidCodePoint => "host-invalid-code-point",
+            Self::Ipv4EmptyPart => "IPv4-empty-part",
+            Self::Ipv4TooManyParts => "IPv4-too-many-parts",
+            Self::Ipv4NonNumericPart => "IPv4-non-numeric-part",
+            Self::Ipv4NonDecimalPart => "IPv4-non-decimal-part",
+            Self::Ipv4OutOfRangePart => "IPv4-out-of-range-part",
+            Self::Ipv4TooFewParts => "IPv4-too-few-parts",
+            Self::Ipv6Unclosed => "IPv6-unclosed",
+            Self::Ipv6InvalidCompression => "IPv6-invalid-compression",
+            Self::Ipv6MultipleCompression => "IPv6-multiple-compression",
+            Self::Ipv6TooManyPieces => "IPv6-too-many-pieces",
+            Self::Ipv6InvalidCodePoint => "IPv6-invalid-code-point",
+            Self::Ipv6TooFewPieces => "IPv6-too-few-pieces",
+            Self::Ipv4InIpv6TooManyPieces => "IPv4-in-IPv6-too-many-pieces",
+            Self::Ipv4InIpv6InvalidCodePoint => "IPv4-in-IPv6-invalid-code-point",
+            Self::Ipv4InIpv6OutOfRangePart => "IPv4-in-IPv6-out-of-range-part",
+            Self::Ipv4InIpv6TooFewParts => "IPv4-in-IPv6-too-few-parts",
         }
     }
 }
@@ -71,29 +133,32 @@ impl HostError {
 impl fmt::Display for HostError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            // Self::DomainInvalidCodePoint => true,
-            // Self::HostInvalidCodePoint => true,
+            Self::Ipv4Failure => write!(f, "IPv4 address could not be parsed!"),
+            Self::DomainToAscii => write!(f, "Domain could not be converted to ASCII!"),
+            Self::DomainInvalidCodePoint => write!(f, "Domain contains a forbidden domain code point!"),
+            Self::HostInvalidCodePoint => write!(f, "Opaque host contains a forbidden host code point!"),
             Self::Ipv4EmptyPart => write!(f, "IPv4 address ends with a U+002E(.)!"),
-            // Self::Ipv4TooManyParts => true,
-            // Self::Ipv4NonNumericPart => true,
-            // Self::Ipv4NonDecimalPart => false,
-            // Self::Ipv4OutOfRangePart => todo!(),
-            // Self::Ipv6Unclosed => true, 
-            // Self::Ipv6InvalidCompression => true,
-            // Self::Ipv6TooManyPieces => true,
-            // Self::Ipv6InvalidCodePoint => true,
-            // Self::Ipv6TooFewPieces => true,
-            // Self::Ipv4InIpv6TooManyPieces => true,
-            // Self::Ipv4InIpv6InvalidCodePoint => true,
-            // Self::Ipv4InIpv6OutOfRangePart => true,
-            // Self::Ipv4InIpv6TooFewParts => true,
-            _ => todo!(),
+            Self::Ipv4TooManyParts => write!(f, "IPv4 address does not consist of exactly 4 parts!"),
+            Self::Ipv4NonNumericPart => write!(f, "IPv4 address part is not numeric!"),
+            Self::Ipv4NonDecimalPart => write!(f, "IPv4 address contains hexadecimal or octal digits!"),
+            Self::Ipv4OutOfRangePart => write!(f, "IPv4 address part exceeds 255!"),
+            Self::Ipv4TooFewParts => write!(f, "IPv4 address has fewer than four parts!"),
+            Self::Ipv6Unclosed => write!(f, "IPv6 address is missing the closing U+005D(])!"),
+            Self::Ipv6InvalidCompression => write!(f, "IPv6 address begins with improper compression!"),
+            Self::Ipv6MultipleCompression => write!(f, "IPv6 address contains multiple instances of '::'!"),
+            Self::Ipv6TooManyPieces => write!(f, "IPv6 address contains more than 8 pieces!"),
+            Self::Ipv6InvalidCodePoint => write!(f, "IPv6 address contains a code point that is neither an ASCII hex digit nor a U+003A(:)!"),
+            Self::Ipv6TooFewPieces => write!(f, "Uncompressed IPv6 address contains fewer than 8 pieces!"),
+            Self::Ipv4InIpv6TooManyPieces => write!(f, "IPv6 address with IPv4 address syntax has more than 6 pieces!"),
+            Self::Ipv4InIpv6InvalidCodePoint => write!(f, "IPv6 address with IPv4 address syntax contains an invalid code point!"),
+            Self::Ipv4InIpv6OutOfRangePart => write!(f, "IPv6 address with IPv4 address syntax has a part exceeding 255!"),
+            Self::Ipv4InIpv6TooFewParts => write!(f, "IPv6 address with IPv4 address syntax has too few parts!"),
         }
     }
 }
 
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum UrlError {
     InvalidUrlUnit,
     SSMissingFollowingSolidus,
@@ -110,27 +175,167 @@ pub enum UrlError {
 impl std::fmt::Display for UrlError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::InvalidUrlUnit => write!(f, "Code point is not a URL unit!"),
             Self::SSMissingFollowingSolidus => write!(f, "Special scheme missing following solidus!"),
+            Self::MissingSchemeNonRelativeUrl => write!(f, "Input is missing a scheme and no usable base URL was given!"),
+            Self::InvalidReverseSolidus => write!(f, "URL has a special scheme and uses U+005C(\\) instead of U+002F(/)!"),
             Self::InvalidCredentials => write!(f, "Invalid credentials!"),
-            _ => todo!(),
+            Self::HostMissing => write!(f, "URL has a special scheme but no host!"),
+            Self::PortOutOfRange => write!(f, "Port is too big!"),
+            Self::PortInvalid => write!(f, "Port is not a number!"),
+            Self::FileInvalidWdl => write!(f, "Relative file URL starts with a Windows drive letter!"),
+            Self::FileInvalidWdlHost => write!(f, "File URL host is a Windows drive letter!"),
         }
     }
 }
 
 
 impl UrlError {
-    fn should_fail(self: Self) -> bool {
+    // Reference: https://url.spec.whatwg.org/#validation-error
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::InvalidUrlUnit => "invalid-URL-unit",
+            Self::SSMissingFollowingSolidus => "special-scheme-missing-following-solidus",
+            Self::MissingSchemeNonRelativeUrl => "missing-scheme-non-relative-URL",
+            Self::InvalidReverseSolidus => "invalid-reverse-solidus",
+            Self::InvalidCredentials => "invalid-credentials",
+            Self::HostMissing => "host-missing",
+            Self::PortOutOfRange => "port-out-of-range",
+            Self::PortInvalid => "port-invalid",
+            Self::FileInvalidWdl => "file-invalid-Windows-drive-letter",
+            Self::FileInvalidWdlHost => "file-invalid-Windows-drive-letter-host",
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum DataUrlError {
+    NotDataScheme,
+    MissingComma,
+    InvalidBase64,
+}
+
+impl fmt::Display for DataUrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotDataScheme => write!(f, "URL scheme is not \"data\"!"),
+            Self::MissingComma => write!(f, "data: URL is missing the U+002C(,) before its body!"),
+            Self::InvalidBase64 => write!(f, "data: URL body is not valid forgiving-base64!"),
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum FilePathError {
+    NotFileScheme,
+    NonLocalHost,
+    EncodedSeparator,
+    EncodedNul,
+    RelativePath,
+    ParentDirectory,
+    InvalidUrl,
+    OpaquePath,
+}
+
+impl fmt::Display for FilePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFileScheme => write!(f, "URL scheme is not \"file\"!"),
+            Self::NonLocalHost => write!(f, "file: URL host is neither empty nor \"localhost\"!"),
+            Self::EncodedSeparator => write!(f, "file: URL path segment contains an encoded U+002F(/)!"),
+            Self::EncodedNul => write!(f, "file: URL path segment contains an encoded U+0000(NULL)!"),
+            Self::RelativePath => write!(f, "Path is not absolute!"),
+            Self::ParentDirectory => write!(f, "Path contains a \"..\" component!"),
+            Self::InvalidUrl => write!(f, "Path does not form a valid file: URL!"),
+            Self::OpaquePath => write!(f, "file: URL has an opaque path!"),
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum MailtoError {
+    NotMailtoScheme,
+    InvalidUtf8,
+}
+
+impl fmt::Display for MailtoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotMailtoScheme => write!(f, "URL is not a \"mailto\" URL with an opaque path!"),
+            Self::InvalidUtf8 => write!(f, "mailto: URL component does not percent-decode to UTF-8!"),
+        }
+    }
+}
+
+
+#[derive(Debug)]
+pub enum PublicSuffixListError {
+    Io(std::io::Error),
+    InvalidList(publicsuffix::Error),
+    InvalidRule(String),
+}
+
+impl fmt::Display for PublicSuffixListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Public suffix list could not be read: {}!", error),
+            Self::InvalidList(error) => write!(f, "Public suffix list is invalid: {}!", error),
+            Self::InvalidRule(rule) => write!(f, "Public suffix rule {:?} is not a valid domain!", rule),
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum CookieError {
+    InvalidDomain,
+    DomainMismatch,
+    PublicSuffixDomain,
+}
+
+impl fmt::Display for CookieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDomain => write!(f, "Cookie Domain attribute is not a valid domain!"),
+            Self::DomainMismatch => write!(f, "Request host does not domain-match the cookie Domain attribute!"),
+            Self::PublicSuffixDomain => write!(f, "Cookie Domain attribute is a public suffix!"),
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum HostSuffixSetError {
+    EmptyEntry,
+    InvalidHost(HostError),
+}
+
+impl fmt::Display for HostSuffixSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyEntry => write!(f, "Host suffix set entry is empty!"),
+            Self::InvalidHost(error) => write!(f, "Host suffix set entry is not a valid host: {}", error),
+        }
+    }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub enum IpNetworkError {
+    InvalidAddress,
+    InvalidPrefixLength,
+    HostBitsSet,
+}
+
+impl fmt::Display for IpNetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::InvalidUrlUnit => false,
-            UrlError::SSMissingFollowingSolidus => false,
-            Self::MissingSchemeNonRelativeUrl => true,
-            Self::InvalidReverseSolidus => false,
-            Self::InvalidCredentials => todo!(),
-            Self::HostMissing => true,
-            Self::PortOutOfRange => true,
-            Self::PortInvalid => true,
-            Self::FileInvalidWdl => false,
-            Self::FileInvalidWdlHost => false,
+            Self::InvalidAddress => write!(f, "IP network address could not be parsed!"),
+            Self::InvalidPrefixLength => write!(f, "IP network prefix length is out of range!"),
+            Self::HostBitsSet => write!(f, "IP network address has bits set beyond its prefix length!"),
         }
     }
 }
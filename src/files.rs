@@ -0,0 +1,182 @@
+use std::ffi::OsString;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Component, Path, PathBuf};
+
+use crate::errors::FilePathError;
+use crate::percent_encoding::{percent_decode_string, percent_encode_byte, PercentEncodeSet};
+use crate::types::types::{HostType, ParseOptions, URL};
+use crate::urls::basic_url_parser;
+
+// U+0025(%) and U+005C(\) are not in the path percent-encode set, but the parser would otherwise
+// decode the former and split file: URL paths on the latter.
+fn encode_path_segment(segment: &[u8], is_first_segment: bool) -> String {
+    let mut output: String = String::with_capacity(segment.len());
+
+    // A first segment like "C|" would be normalized to the Windows drive letter "C:".
+    let is_windows_drive_letter_quirk = is_first_segment
+        && segment.len() == 2
+        && segment[0].is_ascii_alphabetic()
+        && segment[1] == b'|';
+
+    for byte in segment {
+        if PercentEncodeSet::Path.contains(*byte) || *byte == b'%' || *byte == b'\\' || (is_windows_drive_letter_quirk && *byte == b'|') {
+            output += &percent_encode_byte(*byte);
+        } else {
+            output.push(*byte as char);
+        }
+    }
+
+    output
+}
+
+fn file_url_from_path(path: &Path, is_directory: bool) -> Result<URL, FilePathError> {
+    if !path.is_absolute() {
+        return Err(FilePathError::RelativePath);
+    }
+
+    let mut segments: Vec<String> = Vec::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(segment) => {
+                segments.push(encode_path_segment(segment.as_bytes(), segments.is_empty()));
+            }
+            // Dropping the previous segment would give the wrong path when it is a symlink, and the
+            // parser resolves a ".." segment (even percent-encoded) the same way.
+            Component::ParentDir => return Err(FilePathError::ParentDirectory),
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+        }
+    }
+
+    let mut input: String = "file://".to_string();
+    for segment in &segments {
+        input += "/";
+        input += segment;
+    }
+
+    if is_directory || segments.is_empty() {
+        input += "/";
+    }
+
+    basic_url_parser(input, None, None, None, &ParseOptions::default()).map_err(|_| FilePathError::InvalidUrl)
+}
+
+impl URL {
+    pub fn to_file_path(&self) -> Result<PathBuf, FilePathError> {
+        if self.scheme != "file" {
+            return Err(FilePathError::NotFileScheme);
+        }
+
+        if let Some(host) = &self.host {
+            match host.host_type {
+                HostType::Empty => {},
+                HostType::Domain if host.value == "localhost" => {},
+                _ => return Err(FilePathError::NonLocalHost),
+            }
+        }
+
+        let segments: &[String] = self.path.segments().ok_or(FilePathError::OpaquePath)?;
+        let mut bytes: Vec<u8> = Vec::new();
+
+        for segment in segments {
+            let decoded: Vec<u8> = percent_decode_string(segment);
+
+            if decoded.contains(&b'/') {
+                return Err(FilePathError::EncodedSeparator);
+            }
+
+            if decoded.contains(&0) {
+                return Err(FilePathError::EncodedNul);
+            }
+
+            bytes.push(b'/');
+            bytes.extend(decoded);
+        }
+
+        if bytes.is_empty() {
+            bytes.push(b'/');
+        }
+
+        Ok(PathBuf::from(OsString::from_vec(bytes)))
+    }
+
+    pub fn from_file_path(path: &Path) -> Result<URL, FilePathError> {
+        file_url_from_path(path, false)
+    }
+
+    // Like `from_file_path`, but the URL always ends with U+002F(/) so that it can be used as a
+    // base URL for the directory's entries.
+    pub fn from_directory_path(path: &Path) -> Result<URL, FilePathError> {
+        file_url_from_path(path, true)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn parse(input: &str) -> URL {
+        basic_url_parser(input.to_string(), None, None, None, &ParseOptions::default()).unwrap()
+    }
+
+    fn round_trip(path: &Path) -> PathBuf {
+        URL::from_file_path(path).unwrap().to_file_path().unwrap()
+    }
+
+    #[test]
+    fn test_to_file_path() {
+        assert_eq!(parse("file:///tmp/a%20b").to_file_path(), Ok(PathBuf::from("/tmp/a b")));
+        assert_eq!(parse("file://localhost/etc/hosts").to_file_path(), Ok(PathBuf::from("/etc/hosts")));
+        assert_eq!(parse("file:///").to_file_path(), Ok(PathBuf::from("/")));
+        assert_eq!(parse("file:///a/%FF").to_file_path(), Ok(PathBuf::from(OsStr::from_bytes(b"/a/\xFF"))));
+        assert_eq!(parse("file://server/share").to_file_path(), Err(FilePathError::NonLocalHost));
+        assert_eq!(parse("file://127.0.0.1/").to_file_path(), Err(FilePathError::NonLocalHost));
+        assert_eq!(parse("file:///a%2Fb").to_file_path(), Err(FilePathError::EncodedSeparator));
+        assert_eq!(parse("file:///a%00b").to_file_path(), Err(FilePathError::EncodedNul));
+        assert_eq!(parse("data:,foo").to_file_path(), Err(FilePathError::NotFileScheme));
+
+        let mut url = parse("data:,foo");
+        url.scheme = "file".to_string();
+        assert_eq!(url.to_file_path(), Err(FilePathError::OpaquePath));
+    }
+
+    #[test]
+    fn test_from_file_path() {
+        let url = URL::from_file_path(Path::new("/tmp/a b/100%/#?/\\")).unwrap();
+        assert_eq!(url.serialize(None), "file:///tmp/a%20b/100%25/%23%3F/%5C");
+
+        let url = URL::from_file_path(Path::new(OsStr::from_bytes(b"/caf\xE9"))).unwrap();
+        assert_eq!(url.serialize(None), "file:///caf%E9");
+
+        assert_eq!(URL::from_file_path(Path::new("/")).unwrap().serialize(None), "file:///");
+        assert_eq!(URL::from_file_path(Path::new("/C|/x")).unwrap().serialize(None), "file:///C%7C/x");
+        assert_eq!(URL::from_file_path(Path::new("/a/./b/c")).unwrap().serialize(None), "file:///a/b/c");
+        assert_eq!(URL::from_file_path(Path::new("/a/b/../c")).err(), Some(FilePathError::ParentDirectory));
+        assert_eq!(URL::from_directory_path(Path::new("/a/..")).err(), Some(FilePathError::ParentDirectory));
+        assert_eq!(URL::from_file_path(Path::new("relative")).err(), Some(FilePathError::RelativePath));
+
+        assert_eq!(URL::from_directory_path(Path::new("/tmp")).unwrap().serialize(None), "file:///tmp/");
+        assert_eq!(URL::from_directory_path(Path::new("/")).unwrap().serialize(None), "file:///");
+    }
+
+    #[test]
+    fn test_file_path_round_trip() {
+        let paths: Vec<PathBuf> = vec![
+            PathBuf::from("/tmp/with space"),
+            PathBuf::from("/tmp/100%"),
+            PathBuf::from("/tmp/%2F"),
+            PathBuf::from("/tmp/a#b"),
+            PathBuf::from("/tmp/a?b"),
+            PathBuf::from("/tmp/C:"),
+            PathBuf::from("/C|"),
+            PathBuf::from("/tmp/\u{1F980}"),
+            PathBuf::from(OsStr::from_bytes(b"/tmp/\xFF\xFEnot-utf8")),
+        ];
+
+        for path in paths {
+            assert_eq!(round_trip(&path), path);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::str::FromStr;
+
+use crate::errors::HostSuffixSetError;
+use crate::hosts::host_parser;
+use crate::types::types::{Host, HostSuffixMatch, HostSuffixNode, HostSuffixSet, HostType};
+
+// Entries go through the host parser, so "bücher.de" and "xn--bcher-kva.de" are the same entry,
+// and "0x7f.1" is the IP address 127.0.0.1. An IPv6 entry may be given with or without brackets.
+fn parse_entry(entry: &str) -> Result<Host, HostSuffixSetError> {
+    if entry.is_empty() {
+        return Err(HostSuffixSetError::EmptyEntry);
+    }
+
+    let host = if entry.contains(':') && !entry.starts_with('[') {
+        host_parser(&format!("[{}]", entry), false)
+    } else {
+        host_parser(entry, false)
+    };
+
+    host.map_err(HostSuffixSetError::InvalidHost)
+}
+
+impl HostSuffixSet {
+    pub fn new() -> Self {
+        HostSuffixSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Adds `entry`, a domain or IP address. With `HostSuffixMatch::IncludeSubdomains` a domain
+    // also matches every host below it. IP addresses have no subdomains, so they always match
+    // exactly. Inserting an existing domain again keeps the broader of the two matches.
+    pub fn insert(&mut self, entry: &str, host_match: HostSuffixMatch) -> Result<(), HostSuffixSetError> {
+        let host: Host = parse_entry(entry)?;
+
+        let labels = match &host.host_type {
+            HostType::IPAddress(address) => {
+                if self.addresses.insert(address.clone()) {
+                    self.len += 1;
+                }
+                return Ok(());
+            },
+            _ => host.labels().ok_or(HostSuffixSetError::EmptyEntry)?,
+        };
+
+        let mut node: &mut HostSuffixNode = &mut self.root;
+        for label in labels.rev() {
+            node = node.children.entry(label.to_ascii_lowercase()).or_default();
+        }
+
+        node.entry = match node.entry {
+            None => {
+                self.len += 1;
+                Some(host_match)
+            },
+            Some(HostSuffixMatch::IncludeSubdomains) => Some(HostSuffixMatch::IncludeSubdomains),
+            Some(HostSuffixMatch::Exact) => Some(host_match),
+        };
+
+        Ok(())
+    }
+
+    // True if `host` is an entry, or if one of its parent domains is an entry that includes
+    // subdomains. Opaque and empty hosts never match.
+    pub fn contains(&self, host: &Host) -> bool {
+        let labels = match &host.host_type {
+            HostType::IPAddress(address) => return self.addresses.contains(address),
+            HostType::Domain => match host.labels() {
+                Some(labels) => labels,
+                None => return false,
+            },
+            HostType::Opaque | HostType::Empty => return false,
+        };
+
+        let mut node: &HostSuffixNode = &self.root;
+        for label in labels.rev() {
+            if node.entry == Some(HostSuffixMatch::IncludeSubdomains) {
+                return true;
+            }
+
+            node = match node.children.get(label).or_else(|| node.children.get(&label.to_ascii_lowercase())) {
+                Some(child) => child,
+                None => return false,
+            };
+        }
+
+        node.entry.is_some()
+    }
+}
+
+// One entry per line, in the style of Squid's dstdomain lists: "example.com" matches only that
+// host, while ".example.com" matches it and all of its subdomains. Blank lines and lines starting
+// with "#" are skipped.
+impl FromStr for HostSuffixSet {
+    type Err = HostSuffixSetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = HostSuffixSet::new();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.strip_prefix('.') {
+                Some(domain) => set.insert(domain, HostSuffixMatch::IncludeSubdomains)?,
+                None => set.insert(line, HostSuffixMatch::Exact)?,
+            }
+        }
+
+        Ok(set)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::HostError;
+
+    fn host(input: &str) -> Host {
+        host_parser(input, false).unwrap()
+    }
+
+    #[test]
+    fn test_domains() {
+        let set: HostSuffixSet = "# blocked\n\nexample.com\n.ads.example.net\n  .Tracker.ORG.  \n".parse().unwrap();
+        assert_eq!(set.len(), 3);
+
+        assert!(set.contains(&host("example.com")));
+        assert!(set.contains(&host("example.com.")));
+        assert!(set.contains(&host("EXAMPLE.com")));
+        assert!(!set.contains(&host("www.example.com")));
+        assert!(!set.contains(&host("com")));
+
+        assert!(set.contains(&host("ads.example.net")));
+        assert!(set.contains(&host("a.b.ads.example.net")));
+        assert!(!set.contains(&host("example.net")));
+        assert!(!set.contains(&host("bads.example.net")));
+
+        assert!(set.contains(&host("tracker.org")));
+        assert!(set.contains(&host("cdn.tracker.org")));
+
+        assert!(!set.contains(&Host::new("example.com".to_string(), HostType::Opaque)));
+        assert!(!set.contains(&Host::new("".to_string(), HostType::Empty)));
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut set = HostSuffixSet::new();
+        assert!(set.is_empty());
+
+        set.insert("example.com", HostSuffixMatch::IncludeSubdomains).unwrap();
+        set.insert("example.com", HostSuffixMatch::Exact).unwrap();
+        set.insert("www.example.com", HostSuffixMatch::Exact).unwrap();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&host("mail.example.com")));
+
+        set.insert("b\u{FC}cher.de", HostSuffixMatch::Exact).unwrap();
+        set.insert("xn--bcher-kva.de", HostSuffixMatch::Exact).unwrap();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&host("xn--bcher-kva.de")));
+        assert!(set.contains(&host("B\u{DC}CHER.de")));
+
+        assert_eq!(set.insert("", HostSuffixMatch::Exact), Err(HostSuffixSetError::EmptyEntry));
+        assert_eq!(set.insert("exa mple.com", HostSuffixMatch::Exact), Err(HostSuffixSetError::InvalidHost(HostError::DomainInvalidCodePoint)));
+        assert_eq!(set.insert("[::1", HostSuffixMatch::Exact), Err(HostSuffixSetError::InvalidHost(HostError::Ipv6Unclosed)));
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_ip_addresses() {
+        let set: HostSuffixSet = "127.0.0.1\n2001:db8::1\n[::ffff:10.0.0.1]\n.192.0.2.1\n".parse().unwrap();
+        assert_eq!(set.len(), 4);
+
+        assert!(set.contains(&host("127.0.0.1")));
+        assert!(set.contains(&host("0x7f.1")));
+        assert!(set.contains(&host("[2001:DB8:0::1]")));
+        assert!(set.contains(&host("[::ffff:a00:1]")));
+        assert!(set.contains(&host("192.0.2.1")));
+        assert!(!set.contains(&host("127.0.0.2")));
+        assert!(!set.contains(&host("[2001:db8::2]")));
+    }
+}
@@ -1,94 +1,318 @@
+use std::borrow::Cow;
+
 use crate::types::types::*;
 use crate::errors::HostError;
 use crate::domains;
+use crate::percent_encoding::{percent_decode_string, utf8_percent_encode, PercentEncodeSet};
 
-use publicsuffix::{List, Psl};
+// UTS #46 maps these to U+002E(.), so they separate labels in U-label input as well.
+const LABEL_SEPARATORS: [char; 4] = ['.', '\u{3002}', '\u{FF0E}', '\u{FF61}'];
 
-lazy_static::lazy_static! {
-    static ref LIST: List = include_str!("../data/psl.dat").parse().unwrap();
+// The matched suffix of `value`, in the form it was given in, and the registrable domain if the
+// host has a label left of its public suffix. Both keep a trailing dot if `value` has one.
+struct PublicSuffixMatch<'a> {
+    public_suffix: &'a str,
+    registrable_domain: Option<&'a str>,
+    section: Option<PublicSuffixSection>,
 }
 
-pub fn get_public_suffix(host: Host) -> Option<String> {
-    match host.host_type {
-        HostType::Domain => {},
-        _ => return None,
+// Returns the byte offset at which the last `count` labels of `value` start.
+fn last_labels_offset(value: &str, count: usize) -> usize {
+    if count == 0 {
+        return value.len();
+    }
+
+    value
+        .char_indices()
+        .filter(|(_, c)| LABEL_SEPARATORS.contains(c))
+        .map(|(index, c)| index + c.len_utf8())
+        .rev()
+        .nth(count - 1)
+        .unwrap_or(0)
+}
+
+// Reference: https://url.spec.whatwg.org/#host-public-suffix
+// Labels are matched on their ASCII form, so uppercase, A-label and U-label inputs all find the
+// same rule, while the returned slices keep the input's own spelling.
+fn match_public_suffix<'a>(value: &'a str, list: &PublicSuffixList, ignore_private: bool) -> Option<PublicSuffixMatch<'a>> {
+    let without_trailing_dot: &str = value
+        .strip_suffix(|c| LABEL_SEPARATORS.contains(&c))
+        .unwrap_or(value);
+
+    // Domain hosts from the URL parser without an A-label are already in this form, so they are
+    // looked up without allocating.
+    let ascii_domain: Cow<'_, str> = match is_lowercase_ascii_domain(without_trailing_dot) {
+        true => Cow::Borrowed(without_trailing_dot),
+        false => Cow::Owned(domains::domain_to_ascii(without_trailing_dot.to_string(), false).ok()?),
+    };
+
+    // A leading or inner empty label has no public suffix.
+    if ascii_domain.split('.').any(str::is_empty) {
+        return None;
+    }
+
+    let ascii_label_count: usize = ascii_domain.split('.').count();
+    if without_trailing_dot.split(|c| LABEL_SEPARATORS.contains(&c)).count() != ascii_label_count {
+        return None;
+    }
+
+    let suffix = list.find(ascii_domain.as_bytes(), ignore_private);
+    let suffix_labels: usize = ascii_domain[ascii_domain.len() - suffix.len..].split('.').count();
+
+    let public_suffix: &str = &value[last_labels_offset(without_trailing_dot, suffix_labels)..];
+    let registrable_domain: Option<&str> = match ascii_label_count > suffix_labels {
+        true => Some(&value[last_labels_offset(without_trailing_dot, suffix_labels + 1)..]),
+        false => None,
+    };
+
+    Some(PublicSuffixMatch { public_suffix, registrable_domain, section: suffix.section })
+}
+
+// Whether domain to ASCII would return `value` unchanged: lowercase ASCII letters, digits,
+// U+002D(-), U+005F(_) and U+002E(.) only, with no "xn--" label to validate.
+fn is_lowercase_ascii_domain(value: &str) -> bool {
+    value.bytes().all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || matches!(byte, b'-' | b'_' | b'.'))
+        && !value.split('.').any(|label| label.starts_with("xn--"))
+}
+
+// Lowercases only when needed, so hosts from the URL parser are always borrowed.
+fn to_lowercase_if_needed(value: &str) -> Cow<'_, str> {
+    match value.chars().any(char::is_uppercase) {
+        true => Cow::Owned(value.to_lowercase()),
+        false => Cow::Borrowed(value),
+    }
+}
+
+impl Host {
+    // Reference: https://url.spec.whatwg.org/#host-public-suffix
+    // None for hosts that are not domains or have an empty label, or when the default list has
+    // no rule for them.
+    pub fn public_suffix(&self) -> Option<Cow<'_, str>> {
+        if !matches!(self.host_type, HostType::Domain) {
+            return None;
+        }
+
+        let list = PublicSuffixList::default_list();
+        let public_suffix: &str = match_public_suffix(&self.value, &list, false)?.public_suffix;
+        Some(to_lowercase_if_needed(public_suffix))
+    }
+
+    // Reference: https://url.spec.whatwg.org/#host-registrable-domain
+    pub fn registrable_domain(&self) -> Option<Cow<'_, str>> {
+        if !matches!(self.host_type, HostType::Domain) {
+            return None;
+        }
+
+        let list = PublicSuffixList::default_list();
+        let registrable_domain: &str = match_public_suffix(&self.value, &list, false)?.registrable_domain?;
+        Some(to_lowercase_if_needed(registrable_domain))
+    }
+}
+
+// Domains produced by the host parser are already in the ASCII form returned by `domain_to_ascii`,
+// so labels are compared ASCII case-insensitively and a trailing root dot is ignored.
+impl Host {
+    fn domain_without_root(&self) -> Option<&str> {
+        if !matches!(self.host_type, HostType::Domain) {
+            return None;
+        }
+
+        Some(self.value.strip_suffix('.').unwrap_or(&self.value))
+    }
+
+    // Iterates labels from the leftmost one; use `.rev()` to start from the top-level domain.
+    pub fn labels(&self) -> Option<impl DoubleEndedIterator<Item = &str>> {
+        Some(self.domain_without_root()?.split('.'))
+    }
+
+    // The domain with its leftmost label removed, or None for a single-label domain.
+    pub fn parent(&self) -> Option<Host> {
+        let (_, parent) = self.domain_without_root()?.split_once('.')?;
+        Some(Host::new(parent.to_string(), HostType::Domain))
     }
 
-    let public_suffix =  LIST.suffix(host.value.as_bytes())
-        .unwrap()
-        .as_bytes();
+    // True if this domain is strictly below `other`, e.g. "www.example.com" is a subdomain of
+    // "example.com", but "example.com" is not a subdomain of itself.
+    pub fn is_subdomain_of(&self, other: &Host) -> bool {
+        let (labels, other_labels) = match (self.labels(), other.labels()) {
+            (Some(labels), Some(other_labels)) => (labels.rev().collect::<Vec<&str>>(), other_labels.rev().collect::<Vec<&str>>()),
+            _ => return false,
+        };
+
+        labels.len() > other_labels.len()
+            && labels.iter().zip(&other_labels).all(|(label, other_label)| label.eq_ignore_ascii_case(other_label))
+    }
 
-    let public_suffix = String::from_utf8(public_suffix.to_vec()).unwrap();
+    pub fn reverse_dns_key(&self) -> Option<ReverseDnsKey> {
+        let key: Vec<String> = self.labels()?.rev().map(|label| label.to_ascii_lowercase()).collect();
+        Some(ReverseDnsKey(key.join(".")))
+    }
+}
 
-    return Some(public_suffix);
+impl ReverseDnsKey {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
+impl Ord for ReverseDnsKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.split('.').cmp(other.0.split('.'))
+    }
+}
 
-pub fn get_registrable_domain(host: Host) -> Option<String> {
+impl PartialOrd for ReverseDnsKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for ReverseDnsKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Looks `host` up in `list`, or in the process-wide default list if it is None. With
+// `ignore_private`, rules from the PRIVATE section are skipped, so "foo.github.io" has the
+// public suffix "io" rather than "github.io".
+pub fn get_public_suffix(host: &Host, list: Option<&PublicSuffixList>, ignore_private: bool) -> Option<PublicSuffix> {
     match host.host_type {
         HostType::Domain => {},
         _ => return None,
     }
 
-    let domain =  LIST.domain(host.value.as_bytes());
-    if domain.is_none() {
-        return None;
+    let default_list = PublicSuffixList::default_list();
+    let list: &PublicSuffixList = list.unwrap_or(&default_list);
+
+    let suffix_match: PublicSuffixMatch = match_public_suffix(&host.value, list, ignore_private)?;
+    Some(PublicSuffix {
+        value: to_lowercase_if_needed(suffix_match.public_suffix).into_owned(),
+        section: suffix_match.section,
+        is_known: suffix_match.section.is_some(),
+    })
+}
+
+
+pub fn get_registrable_domain(host: &Host, list: Option<&PublicSuffixList>, ignore_private: bool) -> Option<String> {
+    match host.host_type {
+        HostType::Domain => {},
+        _ => return None,
     }
 
-    let domain = String::from_utf8(domain.unwrap().as_bytes().to_vec()).unwrap();
+    let default_list = PublicSuffixList::default_list();
+    let list: &PublicSuffixList = list.unwrap_or(&default_list);
 
-    return Some(domain);
+    let registrable_domain: &str = match_public_suffix(&host.value, list, ignore_private)?.registrable_domain?;
+    Some(to_lowercase_if_needed(registrable_domain).into_owned())
 }
 
-fn parse_ipv6_address(input: &[char]) -> Host {
-    let host = Host::new(input.iter().collect(), HostType::IPAddress(IPAddress::IPv6(0_u128)));
-    return host;
+// `input` is the host without its enclosing brackets.
+fn parse_ipv6_address(input: &[char]) -> Result<Host, HostError> {
+    let pieces: Ipv6Pieces = ipv6_parser(input.iter().collect())?;
+    let host = Host::new("".to_string(), HostType::IPAddress(IPAddress::IPv6(Ipv6Address::from(pieces).0)));
+    Ok(host)
 }
 
-fn opaque_host_parsing(input: &[char]) -> Host {
-    let host = Host::new(input.iter().collect(), HostType::Opaque);
-    return host;
+// Reference: https://url.spec.whatwg.org/#concept-opaque-host-parser
+fn opaque_host_parsing(input: &[char]) -> Result<Host, HostError> {
+    if input.iter().any(|c| is_forbidden_host_code_point(*c)) {
+        return Err(HostError::HostInvalidCodePoint);
+    }
+
+    let value: String = input
+        .iter()
+        .map(|c| utf8_percent_encode(*c, PercentEncodeSet::C0Control))
+        .collect();
+
+    let host = Host::new(value, HostType::Opaque);
+    Ok(host)
 }
 
 pub fn host_parser(input: &str, is_not_special: bool) -> Result<Host, HostError> {
+    host_parser_with_options(input, is_not_special, &ParseOptions::default())
+}
+
+// Reference: https://url.spec.whatwg.org/#concept-host-parser
+// `options` affect how domains are converted to ASCII, and in strict mode the first validation
+// error in an IPv4 address is returned instead of being recovered from.
+pub fn host_parser_with_options(input: &str, is_not_special: bool, options: &ParseOptions) -> Result<Host, HostError> {
     let input: Vec<char> = input.chars().collect();
 
-    if input.get(0) == Some(&'[') {
+    if input.first() == Some(&'[') {
         if input.last() != Some(&']') {
-            eprintln!("{}", HostError::Ipv6Unclosed);
             return Err(HostError::Ipv6Unclosed);
         }
 
-        let result = parse_ipv6_address(&input[1..input.len()]);
-        return Ok(result);
+        return parse_ipv6_address(&input[1..input.len() - 1]);
     }
 
     if is_not_special {
-        let result = opaque_host_parsing(&input);
-        return Ok(result);
+        return opaque_host_parsing(&input);
     }
 
-    assert_ne!(input.len(), 0);
+    // The URL parser never passes an empty special host, but domain to ASCII fails on one anyway.
+    if input.is_empty() {
+        return Err(HostError::DomainToAscii);
+    }
 
     // UTF-8 decode without BOM on the percent-decoding of input.
-    let domain: String = String::new();
+    let domain: String = String::from_utf8_lossy(&percent_decode_string(&input.iter().collect::<String>())).to_string();
 
-    let ascii_domain = domains::domain_to_ascii(domain, false);
+    let ascii_domain = domains::domain_to_ascii_with_options(domain, options);
     if ascii_domain.is_err() {
-        return Err(HostError::Ipv4Failure);
+        return Err(HostError::DomainToAscii);
     }
 
-    if ascii_domain.as_ref().unwrap().chars().last().unwrap().is_digit(10) {
-        let ipv4_address = domains::ipv4_parser(input.iter().collect());
-        if let Err(e) = ipv4_address {
-            return Err(HostError::Ipv4Failure);
+    let ascii_domain: String = ascii_domain.unwrap();
+
+    if ascii_domain.chars().any(is_forbidden_domain_code_point) {
+        return Err(HostError::DomainInvalidCodePoint);
+    }
+
+    if ends_in_a_number(&ascii_domain) {
+        let mut validation_errors: Vec<HostError> = Vec::new();
+        let ipv4_address = domains::ipv4_parser_with_validation(&ascii_domain, &mut validation_errors)?;
+        if options.strict && !validation_errors.is_empty() {
+            return Err(validation_errors.remove(0));
         }
-        let result = Host::new("".to_string(), HostType::IPAddress(IPAddress::IPv4(ipv4_address.unwrap())));
+
+        let result = Host::new("".to_string(), HostType::IPAddress(IPAddress::IPv4(ipv4_address)));
         return Ok(result); 
     }
 
-    let result = Host::new(ascii_domain.unwrap(), HostType::Domain);
-    return Ok(result);
+    let result = Host::new(ascii_domain, HostType::Domain);
+    Ok(result)
+}
+
+// Reference: https://url.spec.whatwg.org/#forbidden-host-code-point
+fn is_forbidden_host_code_point(c: char) -> bool {
+    ['\0', '\t', '\n', '\r', ' ', '#', '/', ':', '<', '>', '?', '@', '[', '\\', ']', '^', '|'].contains(&c)
+}
+
+// Reference: https://url.spec.whatwg.org/#forbidden-domain-code-point
+fn is_forbidden_domain_code_point(c: char) -> bool {
+    is_forbidden_host_code_point(c) || c.is_ascii_control() || c == '%'
+}
+
+// Reference: https://url.spec.whatwg.org/#ends-in-a-number-checker
+fn ends_in_a_number(input: &str) -> bool {
+    let mut parts: Vec<&str> = input.split('.').collect();
+
+    if parts.last() == Some(&"") {
+        if parts.len() == 1 {
+            return false;
+        }
+        parts.pop();
+    }
+
+    let last: &str = parts.last().unwrap();
+
+    if !last.is_empty() && last.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+
+    domains::parse_ipv4_number(last).is_ok()
 }
 
 
@@ -96,30 +320,49 @@ pub fn host_serializer(host: &Host) -> String {
     match host.host_type {
         HostType::Empty | HostType::Opaque | HostType::Domain => host.value.to_owned(),
         HostType::IPAddress(IPAddress::IPv4(address)) => {
-            let result = domains::ipv4_serializer(address);
-            return result;
+            
+            domains::ipv4_serializer(address)
         },
         HostType::IPAddress(IPAddress::IPv6(address)) => {
             let result = domains::ipv6_serializer(Ipv6Pieces::from(Ipv6Address(address)));
-            return format!("[{}]", result);
+            format!("[{}]", result)
         },
     }
 }
 
+// Parses an IP address literal as written in certificates and configuration, rather than in URLs.
+// An IPv6 address may be given with or without its brackets.
+pub(crate) fn parse_ip_address(name: &str) -> Option<IPAddress> {
+    if name.contains(':') {
+        let name: &str = name.strip_prefix('[').and_then(|name| name.strip_suffix(']')).unwrap_or(name);
+        let pieces = ipv6_parser(name.to_string()).ok()?;
+        return Some(IPAddress::IPv6(Ipv6Address::from(pieces).0));
+    }
+
+    // Only the dotted-decimal form is accepted here, not the shorthands the URL parser allows.
+    let parts: Vec<&str> = name.split('.').collect();
+    if parts.len() != 4 || parts.iter().any(|part| part.is_empty() || part.len() > 3 || !part.bytes().all(|byte| byte.is_ascii_digit())) {
+        return None;
+    }
+
+    Some(IPAddress::IPv4(domains::ipv4_parser(name.to_string()).ok()?))
+}
+
+// Reference: https://url.spec.whatwg.org/#concept-ipv6-parser
 pub fn ipv6_parser(input: String) -> Result<Ipv6Pieces, HostError> {
-    let mut  ipv6: Ipv6Pieces = [0_u16; 8];
+    let mut ipv6: Ipv6Pieces = [0_u16; 8];
 
     let mut piece_index: usize = 0;
 
     let input: Vec<char> = input.chars().collect();
+    let c = |pointer: usize| -> Option<char> { input.get(pointer).copied() };
 
     let mut compress: Option<usize> = None;
 
     let mut pointer: usize = 0;
 
-    if input[pointer] == ':' {
-        if input[pointer+1] != ':' {
-            eprintln!("{}", HostError::Ipv6InvalidCompression);
+    if c(pointer) == Some(':') {
+        if c(pointer + 1) != Some(':') {
             return Err(HostError::Ipv6InvalidCompression);
         }
 
@@ -128,19 +371,13 @@ pub fn ipv6_parser(input: String) -> Result<Ipv6Pieces, HostError> {
         compress = Some(piece_index);
     }
 
-    loop {
-        if pointer == input.len() {
-            break;
-        }
-
+    while c(pointer).is_some() {
         if piece_index == 8 {
-            eprintln!("{}", HostError::Ipv6TooManyPieces);
             return Err(HostError::Ipv6TooManyPieces);
         }
 
-        if input[pointer] == ':' {
-            if let None = compress  {
-                eprintln!("{}", HostError::Ipv6MultipleCompression);
+        if c(pointer) == Some(':') {
+            if compress.is_some() {
                 return Err(HostError::Ipv6MultipleCompression);
             }
 
@@ -150,112 +387,99 @@ pub fn ipv6_parser(input: String) -> Result<Ipv6Pieces, HostError> {
             continue;
         }
 
-        let mut value: usize = 0;
+        let mut value: u16 = 0;
         let mut length: usize = 0;
 
+        while length < 4 {
+            let digit: u16 = match c(pointer).and_then(|c| c.to_digit(16)) {
+                Some(digit) => digit as u16,
+                None => break,
+            };
 
-        while length < 4 && input[pointer].is_ascii_hexdigit() {
-            value = value * 0x10 + input[pointer] as usize;
+            value = value * 0x10 + digit;
             pointer += 1;
             length += 1;
         }
 
-        if input[pointer] == '.' {
+        if c(pointer) == Some('.') {
             if length == 0 {
-                eprintln!("{}", HostError::Ipv4InIpv6InvalidCodePoint);
                 return Err(HostError::Ipv4InIpv6InvalidCodePoint);
             }
 
             pointer -= length;
 
             if piece_index > 6 {
-                eprintln!("{}", HostError::Ipv4InIpv6TooManyPieces);
                 return Err(HostError::Ipv4InIpv6TooManyPieces);
             }
 
-            let mut numbers_seen = 0;
+            let mut numbers_seen: usize = 0;
 
-            loop { 
-                if pointer == input.len() {
-                    break;
-                }
-
-                let mut ipv4_piece: Option<u8> = None;
+            while c(pointer).is_some() {
+                let mut ipv4_piece: Option<u16> = None;
 
-                if numbers_seen > 0  {
-                    if input[pointer] == '.' && numbers_seen > 4 {
+                if numbers_seen > 0 {
+                    if c(pointer) == Some('.') && numbers_seen < 4 {
                         pointer += 1;
                     } else {
-                        eprintln!("{}", HostError::Ipv4InIpv6InvalidCodePoint);
                         return Err(HostError::Ipv4InIpv6InvalidCodePoint);
                     }
                 }
 
-                if !input[pointer].is_ascii_digit()  {
-                    eprintln!("{}", HostError::Ipv4InIpv6InvalidCodePoint);
+                if !c(pointer).is_some_and(|c| c.is_ascii_digit()) {
                     return Err(HostError::Ipv4InIpv6InvalidCodePoint);
                 }
 
-                while input[pointer].is_ascii_digit() {
-                    let number = input[pointer] as u8;
-                    match ipv4_piece {
-                        None => ipv4_piece = Some(number),
-                        Some(num) =>  {
-                            if num == 0 {
-                                eprintln!("{}", HostError::Ipv4InIpv6InvalidCodePoint);
-                                return Err(HostError::Ipv4InIpv6InvalidCodePoint);
-                            }
-
-                            ipv4_piece = Some(ipv4_piece.unwrap() * 10_u8 + number);
+                while let Some(number) = c(pointer).and_then(|c| c.to_digit(10)) {
+                    let number: u16 = number as u16;
+                    ipv4_piece = match ipv4_piece {
+                        None => Some(number),
+                        Some(0) => {
+                            return Err(HostError::Ipv4InIpv6InvalidCodePoint);
                         }
-                    }
+                        Some(piece) => Some(piece * 10 + number),
+                    };
 
-                    if ipv4_piece.unwrap() > 255  {
-                        eprintln!("{}", HostError::Ipv4InIpv6OutOfRangePart);
+                    if ipv4_piece > Some(255) {
                         return Err(HostError::Ipv4InIpv6OutOfRangePart);
                     }
 
                     pointer += 1;
                 }
 
-                ipv6[piece_index] = ipv6[piece_index] * 0x100 + ipv4_piece.unwrap() as u16;
+                ipv6[piece_index] = ipv6[piece_index] * 0x100 + ipv4_piece.unwrap_or(0);
                 numbers_seen += 1;
 
-                if numbers_seen == 2 || numbers_seen == 4  {
+                if numbers_seen == 2 || numbers_seen == 4 {
                     piece_index += 1;
                 }
             }
-            
+
             if numbers_seen != 4 {
-                eprintln!("{}", HostError::Ipv4InIpv6TooFewParts);
                 return Err(HostError::Ipv4InIpv6TooFewParts);
             }
 
             break;
-        } else if input[pointer] == ':' {
+        } else if c(pointer) == Some(':') {
             pointer += 1;
 
-            if pointer == input.len() {
-                eprintln!("{}", HostError::Ipv6InvalidCodePoint);
+            if c(pointer).is_none() {
                 return Err(HostError::Ipv6InvalidCodePoint);
             }
-        } else if pointer != input.len() {
-            eprintln!("{}", HostError::Ipv6InvalidCodePoint);
+        } else if c(pointer).is_some() {
             return Err(HostError::Ipv6InvalidCodePoint);
         }
 
-        ipv6[piece_index] = value as u16;
+        ipv6[piece_index] = value;
         piece_index += 1;
     }
 
-
     match compress {
-        Some(num) => {
-            let mut swaps = piece_index - num;
+        Some(compress) => {
+            let mut swaps: usize = piece_index - compress;
             piece_index = 7;
 
             while piece_index != 0 && swaps > 0 {
-                ipv6.swap(piece_index, num + swaps - 1);
+                ipv6.swap(piece_index, compress + swaps - 1);
                 piece_index -= 1;
                 swaps -= 1;
             }
@@ -263,11 +487,189 @@ pub fn ipv6_parser(input: String) -> Result<Ipv6Pieces, HostError> {
 
         None => {
             if piece_index != 8 {
-                eprintln!("{}", HostError::Ipv6TooFewPieces);
                 return Err(HostError::Ipv6TooFewPieces);
             }
         }
     }
 
-    return Ok(ipv6);
+    Ok(ipv6)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn test_opaque_host_parser() {
+        let host = host_parser("ex%41mple\u{00E9}", true).unwrap();
+        assert!(matches!(host.host_type, HostType::Opaque));
+        assert_eq!(host.value, "ex%41mple%C3%A9");
+
+        assert!(matches!(host_parser("exa mple", true), Err(HostError::HostInvalidCodePoint)));
+        assert!(matches!(host_parser("a<b", true), Err(HostError::HostInvalidCodePoint)));
+    }
+
+    #[test]
+    fn test_empty_host() {
+        assert!(matches!(host_parser("", false), Err(HostError::DomainToAscii)));
+        assert_eq!(host_parser("", true).unwrap().value, "");
+    }
+
+    fn domain(value: &str) -> Host {
+        Host::new(value.to_string(), HostType::Domain)
+    }
+
+    // Parses a `checkPublicSuffix('input', 'expected');` line, where either argument may be null.
+    fn parse_check(line: &str) -> Option<(Option<&str>, Option<&str>)> {
+        let arguments: &str = line.strip_prefix("checkPublicSuffix(")?.strip_suffix(");")?;
+        let (input, expected) = arguments.split_once(", ")?;
+        Some((parse_argument(input)?, parse_argument(expected)?))
+    }
+
+    fn parse_argument(argument: &str) -> Option<Option<&str>> {
+        match argument {
+            "null" => Some(None),
+            _ => Some(Some(argument.strip_prefix('\'')?.strip_suffix('\'')?)),
+        }
+    }
+
+    // data/test_psl.txt is tests/test_psl.txt from https://github.com/publicsuffix/list, unmodified.
+    // Every line is a comment or a check, and each check is asserted on its own.
+    #[test]
+    fn test_psl_checks() {
+        for line in include_str!("../data/test_psl.txt").lines() {
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+
+            let (input, expected) = parse_check(line).unwrap_or_else(|| panic!("unrecognized check {:?}", line));
+
+            // A null input has no host to look up.
+            let input: &str = match input {
+                Some(input) => input,
+                None => continue,
+            };
+
+            let host = domain(input);
+            assert_eq!(host.registrable_domain().as_deref(), expected, "registrable domain of {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_public_suffix() {
+        assert_eq!(domain("www.example.co.uk").public_suffix().as_deref(), Some("co.uk"));
+        assert_eq!(domain("www.Example.CO.uk.").public_suffix().as_deref(), Some("co.uk."));
+        assert_eq!(domain("www.Example.CO.uk.").registrable_domain().as_deref(), Some("example.co.uk."));
+        assert_eq!(domain("example.unlisted").public_suffix().as_deref(), Some("unlisted"));
+        assert_eq!(domain("com").public_suffix().as_deref(), Some("com"));
+        assert_eq!(domain("com.").registrable_domain(), None);
+        assert_eq!(domain("www.\u{98DF}\u{72EE}\u{3002}\u{516C}\u{53F8}.cn").registrable_domain().as_deref(), Some("\u{98DF}\u{72EE}\u{3002}\u{516C}\u{53F8}.cn"));
+
+        let host = domain("www.example.com");
+        assert!(matches!(host.registrable_domain(), Some(Cow::Borrowed("example.com"))));
+
+        for value in ["", ".", "..", "a..b", ".com", "xn--"] {
+            assert_eq!(domain(value).public_suffix(), None, "public suffix of {:?}", value);
+            assert_eq!(domain(value).registrable_domain(), None, "registrable domain of {:?}", value);
+        }
+
+        assert_eq!(Host::new("".to_string(), HostType::Empty).public_suffix(), None);
+        assert_eq!(Host::new("example".to_string(), HostType::Opaque).registrable_domain(), None);
+    }
+
+    // Skipping domain to ASCII must not change the result for any input it is skipped for.
+    #[test]
+    fn test_lowercase_ascii_domain() {
+        for value in ["www.example.com", "a_b.c-d.com", "-a.b-.com", "1.2.3.4", "a..b", ".com"] {
+            assert!(is_lowercase_ascii_domain(value), "{:?}", value);
+            assert_eq!(domains::domain_to_ascii(value.to_string(), false).ok().as_deref(), Some(value), "{:?}", value);
+        }
+
+        for value in ["www.Example.com", "xn--bcher-kva.de", "xn--", "a b.com", "b\u{FC}cher.de"] {
+            assert!(!is_lowercase_ascii_domain(value), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        let host = domain("www.example.com.");
+        assert_eq!(host.labels().unwrap().collect::<Vec<&str>>(), vec!["www", "example", "com"]);
+        assert_eq!(host.labels().unwrap().rev().collect::<Vec<&str>>(), vec!["com", "example", "www"]);
+        assert_eq!(host.parent().map(|parent| parent.value), Some("example.com".to_string()));
+        assert_eq!(domain("com").parent().map(|parent| parent.value), None);
+        assert!(Host::new("".to_string(), HostType::Opaque).labels().is_none());
+
+        assert!(domain("www.example.com").is_subdomain_of(&domain("example.com.")));
+        assert!(domain("a.b.EXAMPLE.com").is_subdomain_of(&domain("example.com")));
+        assert!(!domain("example.com").is_subdomain_of(&domain("example.com")));
+        assert!(!domain("wwwexample.com").is_subdomain_of(&domain("example.com")));
+        assert!(!domain("example.com").is_subdomain_of(&domain("www.example.com")));
+        assert!(!Host::new("example.com".to_string(), HostType::Opaque).is_subdomain_of(&domain("com")));
+    }
+
+    #[test]
+    fn test_reverse_dns_key() {
+        assert_eq!(domain("www.Example.com.").reverse_dns_key().unwrap().to_string(), "com.example.www");
+
+        let mut keys: Vec<ReverseDnsKey> = ["example-foo.com", "www.example.com", "example.com", "a.example.com", "example.org"]
+            .iter()
+            .map(|value| domain(value).reverse_dns_key().unwrap())
+            .collect();
+        keys.sort();
+
+        let keys: Vec<&str> = keys.iter().map(ReverseDnsKey::as_str).collect();
+        assert_eq!(keys, vec!["com.example", "com.example.a", "com.example.www", "com.example-foo", "org.example"]);
+    }
+
+    #[test]
+    fn test_ipv6_parser() {
+        assert_eq!(ipv6_parser("::1".to_string()), Ok([0, 0, 0, 0, 0, 0, 0, 1]));
+        assert_eq!(ipv6_parser("2001:DB8::1:0:0:1".to_string()), Ok([0x2001, 0xdb8, 0, 0, 1, 0, 0, 1]));
+        assert_eq!(ipv6_parser("1:2:3:4:5:6:7:8".to_string()), Ok([1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(ipv6_parser("::ffff:192.0.2.1".to_string()), Ok([0, 0, 0, 0, 0, 0xffff, 0xc000, 0x0201]));
+        assert_eq!(ipv6_parser("::".to_string()), Ok([0; 8]));
+
+        assert_eq!(ipv6_parser(":1".to_string()), Err(HostError::Ipv6InvalidCompression));
+        assert_eq!(ipv6_parser("1::2::3".to_string()), Err(HostError::Ipv6MultipleCompression));
+        assert_eq!(ipv6_parser("1:2:3:4:5:6:7:8:9".to_string()), Err(HostError::Ipv6TooManyPieces));
+        assert_eq!(ipv6_parser("1:2:3".to_string()), Err(HostError::Ipv6TooFewPieces));
+        assert_eq!(ipv6_parser("1:".to_string()), Err(HostError::Ipv6InvalidCodePoint));
+        assert_eq!(ipv6_parser("12345::".to_string()), Err(HostError::Ipv6InvalidCodePoint));
+        assert_eq!(ipv6_parser("::1.2.3".to_string()), Err(HostError::Ipv4InIpv6TooFewParts));
+        assert_eq!(ipv6_parser("::1.2.3.256".to_string()), Err(HostError::Ipv4InIpv6OutOfRangePart));
+        assert_eq!(ipv6_parser("::1.2.3.04".to_string()), Err(HostError::Ipv4InIpv6InvalidCodePoint));
+        assert_eq!(ipv6_parser("1:2:3:4:5:6:7:1.2.3.4".to_string()), Err(HostError::Ipv4InIpv6TooManyPieces));
+    }
+
+    #[test]
+    fn test_ipv6_host() {
+        let host = host_parser("[2001:DB8:0:0::1]", false).unwrap();
+        assert_eq!(host_serializer(&host), "[2001:db8::1]");
+        assert!(host_parser("[::1", false).is_err());
+        assert!(host_parser("[::g]", false).is_err());
+    }
+
+    fn host_strategy() -> impl Strategy<Value = String> {
+        let ipv4 = any::<u32>().prop_map(|address| Ipv4Addr::from(address).to_string());
+        let ipv6 = any::<u128>().prop_map(|address| format!("[{}]", Ipv6Addr::from(address)));
+        let domain = "[a-zA-Z0-9\\-\u{E9}\u{DF}\u{430}%_]{1,8}(\\.[a-zA-Z0-9\\-\u{FC}]{0,8}){0,3}\\.?";
+        let numbers = "(0x[0-9a-f]{0,4}|0[0-7]{0,4}|[0-9]{1,5})(\\.(0x[0-9a-f]{0,4}|0[0-7]{0,4}|[0-9]{1,5})){0,4}";
+
+        prop_oneof![ipv4, ipv6, domain.prop_map(String::from), numbers.prop_map(String::from)]
+    }
+
+    proptest! {
+        #[test]
+        fn test_host_serializer_round_trip(input in host_strategy(), is_not_special in any::<bool>()) {
+            if let Ok(host) = host_parser(&input, is_not_special) {
+                let serialized = host_serializer(&host);
+                let reparsed = host_parser(&serialized, is_not_special);
+                prop_assert!(reparsed.is_ok(), "{:?} serialized as {:?}", input, serialized);
+                prop_assert_eq!(host_serializer(&reparsed.unwrap()), serialized);
+            }
+        }
+    }
 }
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use idna::punycode;
+use unicode_script::{Script, UnicodeScript};
+use unicode_security::{skeleton, GeneralSecurityProfile, RestrictionLevel, RestrictionLevelDetection};
+
+use crate::hosts::host_serializer;
+use crate::types::types::{DisplayLabel, Host, HostType, IdnFallbackReason};
+
+// Scripts that a TLD's registry permits in its labels, keyed by the Unicode form of the TLD.
+// Labels under a TLD listed here may only use these scripts, and may be whole-script confusable
+// with Latin in them, e.g. "сосо.рф". Labels under any other TLD may use any script.
+static TLD_SCRIPTS: &[(&str, &[Script])] = &[
+    ("bg", &[Script::Latin, Script::Cyrillic]),
+    ("by", &[Script::Latin, Script::Cyrillic]),
+    ("cn", &[Script::Latin, Script::Han]),
+    ("cy", &[Script::Latin, Script::Greek]),
+    ("gr", &[Script::Latin, Script::Greek]),
+    ("il", &[Script::Latin, Script::Hebrew]),
+    ("jp", &[Script::Latin, Script::Han, Script::Hiragana, Script::Katakana]),
+    ("kr", &[Script::Latin, Script::Hangul, Script::Han]),
+    ("kz", &[Script::Latin, Script::Cyrillic]),
+    ("mk", &[Script::Latin, Script::Cyrillic]),
+    ("mn", &[Script::Latin, Script::Cyrillic]),
+    ("rs", &[Script::Latin, Script::Cyrillic]),
+    ("ru", &[Script::Latin, Script::Cyrillic]),
+    ("su", &[Script::Latin, Script::Cyrillic]),
+    ("tw", &[Script::Latin, Script::Han, Script::Bopomofo]),
+    ("ua", &[Script::Latin, Script::Cyrillic]),
+    ("ελ", &[Script::Greek]),
+    ("бг", &[Script::Cyrillic]),
+    ("бел", &[Script::Cyrillic]),
+    ("мкд", &[Script::Cyrillic]),
+    ("мон", &[Script::Cyrillic]),
+    ("рф", &[Script::Cyrillic]),
+    ("срб", &[Script::Cyrillic]),
+    ("укр", &[Script::Cyrillic]),
+    ("қаз", &[Script::Cyrillic]),
+    ("中国", &[Script::Han]),
+    ("한국", &[Script::Hangul, Script::Han]),
+];
+
+fn tld_scripts(tld: &str) -> Option<&'static [Script]> {
+    TLD_SCRIPTS.iter().find(|(name, _)| *name == tld).map(|(_, scripts)| *scripts)
+}
+
+// Returns the U-label for an A-label, or None if it is not a valid A-label. Decoding and encoding
+// again must give back the same label, so that unnormalized or otherwise invalid Punycode is
+// never displayed.
+fn to_unicode_label(label: &str) -> Option<String> {
+    let unicode: String = punycode::decode_to_string(label.strip_prefix("xn--")?)?;
+    if idna::domain_to_ascii(&unicode).ok()? != label {
+        return None;
+    }
+
+    Some(unicode)
+}
+
+// The scripts used by `label`, ignoring characters such as digits and "-" that are shared by all
+// scripts.
+fn label_scripts(label: &str) -> HashSet<Script> {
+    label
+        .chars()
+        .map(|c| c.script())
+        .filter(|script| !matches!(script, Script::Common | Script::Inherited))
+        .collect()
+}
+
+// Modeled on the IDN display policies of Chromium and Firefox.
+// Reference: https://chromium.googlesource.com/chromium/src/+/main/docs/idn.md
+// Reference: https://www.unicode.org/reports/tr39/#Restriction_Level_Detection
+fn check_label(unicode: &str, allowed_scripts: Option<&[Script]>) -> Option<IdnFallbackReason> {
+    if let Some(c) = unicode.chars().find(|c| !c.identifier_allowed()) {
+        return Some(IdnFallbackReason::DisallowedCharacter(c));
+    }
+
+    if !unicode.check_restriction_level(RestrictionLevel::HighlyRestrictive) {
+        return Some(IdnFallbackReason::MixedScript);
+    }
+
+    let scripts: HashSet<Script> = label_scripts(unicode);
+    if let Some(allowed_scripts) = allowed_scripts {
+        if !scripts.iter().all(|script| allowed_scripts.contains(script)) {
+            return Some(IdnFallbackReason::ScriptNotAllowedForTld);
+        }
+    }
+
+    // A label written entirely in one non-Latin script whose skeleton is plain ASCII, like
+    // Cyrillic "аррӏе", can pass for a Latin label.
+    let whole_script_confusable: bool = scripts.len() == 1
+        && !scripts.contains(&Script::Latin)
+        && !allowed_scripts.is_some_and(|allowed_scripts| scripts.iter().all(|script| allowed_scripts.contains(script)))
+        && skeleton(unicode).all(|c| c.is_ascii());
+
+    if whole_script_confusable {
+        return Some(IdnFallbackReason::WholeScriptConfusable);
+    }
+
+    None
+}
+
+fn display_label(label: &str, allowed_scripts: Option<&[Script]>) -> DisplayLabel {
+    let label: String = label.to_ascii_lowercase();
+    if !label.starts_with("xn--") {
+        return DisplayLabel { value: label, fallback: None };
+    }
+
+    let unicode: String = match to_unicode_label(&label) {
+        Some(unicode) => unicode,
+        None => return DisplayLabel { value: label, fallback: Some(IdnFallbackReason::InvalidPunycode) },
+    };
+
+    match check_label(&unicode, allowed_scripts) {
+        Some(reason) => DisplayLabel { value: label, fallback: Some(reason) },
+        None => DisplayLabel { value: unicode, fallback: None },
+    }
+}
+
+// Decides, for each label of a domain, whether it is safe to show its U-label. Labels that are
+// not safe keep their A-label and record why. Returns None for hosts that are not domains.
+pub fn display_host_labels(host: &Host) -> Option<Vec<DisplayLabel>> {
+    if !matches!(host.host_type, HostType::Domain) {
+        return None;
+    }
+
+    let labels: Vec<&str> = host.labels()?.collect();
+    let tld: String = labels.last().map(|tld| tld.to_ascii_lowercase()).unwrap_or_default();
+    let tld: String = to_unicode_label(&tld).unwrap_or(tld);
+    let allowed_scripts: Option<&[Script]> = tld_scripts(&tld);
+
+    Some(labels.iter().map(|label| display_label(label, allowed_scripts)).collect())
+}
+
+// The host as it should be shown to users. Unlike `domain_to_unicode`, a label is only shown in
+// Unicode when it passes the display policy, so "xn--80ak6aa92e.com" stays as it is rather than
+// being shown as "аррӏе.com".
+pub fn display_host(host: &Host) -> String {
+    let labels: Vec<DisplayLabel> = match display_host_labels(host) {
+        Some(labels) => labels,
+        None => return host_serializer(host),
+    };
+
+    let mut output: String = labels.into_iter().map(|label| label.value).collect::<Vec<String>>().join(".");
+    if host.value.ends_with('.') {
+        output.push('.');
+    }
+
+    output
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::host_parser;
+
+    fn display(input: &str) -> String {
+        display_host(&host_parser(input, false).unwrap())
+    }
+
+    fn fallbacks(input: &str) -> Vec<Option<IdnFallbackReason>> {
+        let labels = display_host_labels(&host_parser(input, false).unwrap()).unwrap();
+        labels.into_iter().map(|label| label.fallback).collect()
+    }
+
+    #[test]
+    fn test_display_host() {
+        assert_eq!(display("example.com"), "example.com");
+        assert_eq!(display("EXAMPLE.com."), "example.com.");
+        assert_eq!(display("xn--bcher-kva.de"), "b\u{FC}cher.de");
+        assert_eq!(display("b\u{FC}cher.example"), "b\u{FC}cher.example");
+        assert_eq!(display("\u{4F8B}\u{3048}.\u{30C6}\u{30B9}\u{30C8}.jp"), "\u{4F8B}\u{3048}.\u{30C6}\u{30B9}\u{30C8}.jp");
+        assert_eq!(display("\u{043F}\u{0440}\u{0438}\u{043C}\u{0435}\u{0440}.com"), "\u{043F}\u{0440}\u{0438}\u{043C}\u{0435}\u{0440}.com");
+        assert_eq!(display("\u{043F}\u{0440}\u{0438}\u{043C}\u{0435}\u{0440}.\u{0440}\u{0444}"), "\u{043F}\u{0440}\u{0438}\u{043C}\u{0435}\u{0440}.\u{0440}\u{0444}");
+
+        assert_eq!(display("127.0.0.1"), "127.0.0.1");
+        assert_eq!(display("[::1]"), "[::1]");
+        assert_eq!(display_host(&Host::new("xn--bcher-kva.de".to_string(), HostType::Opaque)), "xn--bcher-kva.de");
+    }
+
+    #[test]
+    fn test_fallback_reasons() {
+        // Cyrillic "аррӏе" looks like "apple".
+        assert_eq!(display("\u{0430}\u{0440}\u{0440}\u{04CF}\u{0435}.com"), "xn--80ak6aa92e.com");
+        assert_eq!(fallbacks("xn--80ak6aa92e.com"), vec![Some(IdnFallbackReason::WholeScriptConfusable), None]);
+        assert_eq!(fallbacks("xn--80ak6aa92e.ru"), vec![None, None]);
+
+        // Latin "p" and "l" mixed with Cyrillic "а" and "е".
+        assert_eq!(fallbacks("\u{0430}pp\u{04CF}\u{0435}.com"), vec![Some(IdnFallbackReason::MixedScript), None]);
+        assert_eq!(fallbacks("\u{0430}ppl\u{0435}.com"), vec![Some(IdnFallbackReason::MixedScript), None]);
+
+        assert_eq!(fallbacks("a\u{2665}b.com"), vec![Some(IdnFallbackReason::DisallowedCharacter('\u{2665}')), None]);
+        assert_eq!(fallbacks("b\u{FC}cher.\u{0440}\u{0444}"), vec![Some(IdnFallbackReason::ScriptNotAllowedForTld), None]);
+        assert_eq!(fallbacks("\u{03B1}\u{03B2}\u{03B3}.jp"), vec![Some(IdnFallbackReason::ScriptNotAllowedForTld), None]);
+
+        let host = Host::new("xn--example-.de".to_string(), HostType::Domain);
+        assert_eq!(display_host(&host), "xn--example-.de");
+        assert_eq!(display_host_labels(&host).unwrap()[0].fallback, Some(IdnFallbackReason::InvalidPunycode));
+    }
+}
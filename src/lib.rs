@@ -1,5 +1,20 @@
-mod hosts;
-mod types;
-mod errors;
-mod domains;
-mod urls;
+pub mod hosts;
+pub mod types;
+pub mod errors;
+pub mod domains;
+pub mod public_suffix;
+pub mod urls;
+pub mod options;
+pub mod diagnostics;
+pub mod data_urls;
+pub mod mailto;
+pub mod cookies;
+pub mod host_sets;
+pub mod networks;
+pub mod idn_display;
+pub mod confusables;
+pub mod percent_encoding;
+mod cert_names;
+mod psl_trie;
+#[cfg(unix)]
+mod files;
@@ -0,0 +1,172 @@
+use crate::errors::MailtoError;
+use crate::percent_encoding::{percent_decode_string, percent_encode_byte};
+use crate::types::types::{MailtoUrl, Path, URL};
+
+// Reference: https://www.rfc-editor.org/rfc/rfc6068#section-2
+// qchar = unreserved / pct-encoded / some-delims, where U+002C(,) additionally separates addresses.
+fn is_qchar(byte: u8, is_address: bool) -> bool {
+    byte.is_ascii_alphanumeric()
+        || b"-._~!$'()*+;:@".contains(&byte)
+        || (byte == b',' && !is_address)
+}
+
+fn encode(input: &str, is_address: bool) -> String {
+    let mut output: String = String::with_capacity(input.len());
+
+    for byte in input.bytes() {
+        if is_qchar(byte, is_address) {
+            output.push(byte as char);
+        } else {
+            output += &percent_encode_byte(byte);
+        }
+    }
+
+    output
+}
+
+// Unlike application/x-www-form-urlencoded, U+002B(+) is not a space in mailto: URLs.
+fn decode(input: &str) -> Result<String, MailtoError> {
+    String::from_utf8(percent_decode_string(input)).map_err(|_| MailtoError::InvalidUtf8)
+}
+
+// Addresses are split before decoding so that an encoded U+002C(,) stays inside its address.
+fn decode_addresses(input: &str) -> Result<Vec<String>, MailtoError> {
+    input
+        .split(',')
+        .filter(|address| !address.is_empty())
+        .map(decode)
+        .collect()
+}
+
+fn encode_addresses(addresses: &[String]) -> String {
+    addresses
+        .iter()
+        .map(|address| encode(address, true))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+impl MailtoUrl {
+    pub fn from_url(url: &URL) -> Result<MailtoUrl, MailtoError> {
+        let path: &String = match &url.path {
+            Path::Opaque(path) if url.scheme == "mailto" => path,
+            _ => return Err(MailtoError::NotMailtoScheme),
+        };
+
+        let mut mailto: MailtoUrl = MailtoUrl {
+            to: decode_addresses(path)?,
+            ..MailtoUrl::default()
+        };
+
+        let hfields: &str = url.query.as_deref().unwrap_or("");
+
+        for hfield in hfields.split('&').filter(|hfield| !hfield.is_empty()) {
+            let (name, value) = hfield.split_once('=').unwrap_or((hfield, ""));
+            let name: String = decode(name)?.to_ascii_lowercase();
+
+            // Header field names are case-insensitive, and a repeated subject or body keeps the
+            // first occurrence.
+            match name.as_str() {
+                "to" => mailto.to.extend(decode_addresses(value)?),
+                "cc" => mailto.cc.extend(decode_addresses(value)?),
+                "bcc" => mailto.bcc.extend(decode_addresses(value)?),
+                "subject" if mailto.subject.is_none() => mailto.subject = Some(decode(value)?),
+                "body" if mailto.body.is_none() => mailto.body = Some(decode(value)?),
+                "subject" | "body" => {},
+                _ => mailto.headers.push((name, decode(value)?)),
+            }
+        }
+
+        Ok(mailto)
+    }
+
+    pub fn to_url(&self) -> URL {
+        let mut hfields: Vec<String> = Vec::new();
+
+        if !self.cc.is_empty() {
+            hfields.push(format!("cc={}", encode_addresses(&self.cc)));
+        }
+
+        if !self.bcc.is_empty() {
+            hfields.push(format!("bcc={}", encode_addresses(&self.bcc)));
+        }
+
+        if let Some(subject) = &self.subject {
+            hfields.push(format!("subject={}", encode(subject, false)));
+        }
+
+        for (name, value) in &self.headers {
+            hfields.push(format!("{}={}", encode(name, false), encode(value, false)));
+        }
+
+        if let Some(body) = &self.body {
+            hfields.push(format!("body={}", encode(body, false)));
+        }
+
+        let mut input: String = "mailto:".to_string() + &encode_addresses(&self.to);
+        if !hfields.is_empty() {
+            input += "?";
+            input += &hfields.join("&");
+        }
+
+        URL::parse(&input).expect("A mailto: URL with encoded components is always valid")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mailto(input: &str) -> MailtoUrl {
+        MailtoUrl::from_url(&URL::parse(input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_from_url() {
+        let url = mailto("mailto:alice@example.com,bob@example.com?Subject=Hello%20there&cc=carol@example.com&body=a+b%0D%0Ac");
+        assert_eq!(url.to, vec!["alice@example.com", "bob@example.com"]);
+        assert_eq!(url.cc, vec!["carol@example.com"]);
+        assert_eq!(url.subject.as_deref(), Some("Hello there"));
+        assert_eq!(url.body.as_deref(), Some("a+b\r\nc"));
+
+        let url = mailto("mailto:?to=%22not%2Cwith%22@example.com&bcc=a@b,c@d&In-Reply-To=%3C3469A91.D10AF4C@example.com%3E");
+        assert_eq!(url.to, vec!["\"not,with\"@example.com"]);
+        assert_eq!(url.bcc, vec!["a@b", "c@d"]);
+        assert_eq!(url.headers, vec![("in-reply-to".to_string(), "<3469A91.D10AF4C@example.com>".to_string())]);
+
+        let url = mailto("mailto:user@%E2%98%83.example?subject=%E2%98%83&subject=ignored");
+        assert_eq!(url.to, vec!["user@\u{2603}.example"]);
+        assert_eq!(url.subject.as_deref(), Some("\u{2603}"));
+
+        assert_eq!(MailtoUrl::from_url(&URL::parse("mailto:%FF").unwrap()), Err(MailtoError::InvalidUtf8));
+        assert_eq!(MailtoUrl::from_url(&URL::parse("http://example.com/").unwrap()), Err(MailtoError::NotMailtoScheme));
+        assert_eq!(MailtoUrl::from_url(&URL::parse("mailto://example.com/").unwrap()), Err(MailtoError::NotMailtoScheme));
+    }
+
+    #[test]
+    fn test_to_url() {
+        let url = MailtoUrl {
+            to: vec!["\"a,b\"@example.com".to_string(), "caf\u{E9}@example.com".to_string()],
+            cc: vec!["c@example.com".to_string()],
+            subject: Some("50% off? #1 & more".to_string()),
+            body: Some("line 1\r\nline 2".to_string()),
+            headers: vec![("X-Tag".to_string(), "a=b".to_string())],
+            ..MailtoUrl::default()
+        };
+
+        let serialized = url.to_url().serialize(None);
+        assert_eq!(
+            serialized,
+            "mailto:%22a%2Cb%22@example.com,caf%C3%A9@example.com?cc=c@example.com&subject=50%25%20off%3F%20%231%20%26%20more&X-Tag=a%3Db&body=line%201%0D%0Aline%202"
+        );
+
+        let round_trip = mailto(&serialized);
+        assert_eq!(round_trip.to, url.to);
+        assert_eq!(round_trip.subject, url.subject);
+        assert_eq!(round_trip.body, url.body);
+        assert_eq!(round_trip.headers, vec![("x-tag".to_string(), "a=b".to_string())]);
+
+        assert_eq!(MailtoUrl::default().to_url().serialize(None), "mailto:");
+    }
+}
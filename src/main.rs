@@ -0,0 +1,268 @@
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+use resloc::errors::ReslocError;
+use resloc::hosts::host_serializer;
+use resloc::types::types::{HostType, IPAddress, ParseOptions, URL};
+
+const USAGE: &str = "\
+Usage: resloc [--base <url>] [--strict] [--json] [<url>...]
+
+Parses each URL and prints its components. With no URLs, reads one URL per line from stdin.
+
+Options:
+  --base <url>  Resolve the URLs against <url>
+  --strict      Fail on any validation error, not only those the URL Standard fails on
+  --json        Print one JSON object per URL instead of a table
+  -h, --help    Print this help
+
+Exits with 1 if any URL fails to parse, and with 2 on a usage error.";
+
+struct Args {
+    base: Option<String>,
+    strict: bool,
+    json: bool,
+    inputs: Vec<String>,
+}
+
+enum Field {
+    Null,
+    Text(String),
+    Number(u16),
+    List(Vec<String>),
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args { base: None, strict: false, json: false, inputs: Vec::new() };
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--base" => parsed.base = Some(args.next().ok_or("--base needs a URL")?),
+            "--strict" => parsed.strict = true,
+            "--json" => parsed.json = true,
+            "--" => parsed.inputs.extend(args.by_ref()),
+            _ if arg.starts_with("--base=") => parsed.base = Some(arg["--base=".len()..].to_string()),
+            _ if arg.starts_with('-') && arg.len() > 1 => return Err(format!("unknown option {}", arg)),
+            _ => parsed.inputs.push(arg),
+        }
+    }
+
+    Ok(Some(parsed))
+}
+
+fn host_type(host_type: &HostType) -> &'static str {
+    match host_type {
+        HostType::Domain => "domain",
+        HostType::IPAddress(IPAddress::IPv4(_)) => "ipv4",
+        HostType::IPAddress(IPAddress::IPv6(_)) => "ipv6",
+        HostType::Opaque => "opaque",
+        HostType::Empty => "empty",
+    }
+}
+
+fn optional(value: Option<String>) -> Field {
+    value.map_or(Field::Null, Field::Text)
+}
+
+fn describe(url: &URL) -> Vec<(&'static str, Field)> {
+    let host = url.host.as_ref();
+
+    vec![
+        ("href", Field::Text(url.serialize(None))),
+        ("scheme", Field::Text(url.scheme.clone())),
+        ("username", Field::Text(url.username.clone())),
+        ("password", Field::Text(url.password.clone())),
+        ("host", optional(host.map(host_serializer))),
+        ("host_type", optional(host.map(|host| host_type(&host.host_type).to_string()))),
+        ("port", url.port.map_or(Field::Null, Field::Number)),
+        ("path", Field::Text(url.serialize_path())),
+        ("path_segments", url.path_segments().map_or(Field::Null, |segments| Field::List(segments.collect()))),
+        ("query", optional(url.query.clone())),
+        ("fragment", optional(url.fragment.clone())),
+        ("origin", Field::Text(url.origin())),
+        ("public_suffix", optional(host.and_then(|host| host.public_suffix()).map(String::from))),
+        ("registrable_domain", optional(host.and_then(|host| host.registrable_domain()).map(String::from))),
+    ]
+}
+
+fn error_message(error: &ReslocError) -> String {
+    match error {
+        ReslocError::Failure => "URL could not be parsed!".to_string(),
+        ReslocError::ValidationError { error, .. } => format!("{}: {}", error.name(), error),
+        ReslocError::LimitExceeded(error) => error.to_string(),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut output: String = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => output += "\\\"",
+            '\\' => output += "\\\\",
+            '\n' => output += "\\n",
+            '\r' => output += "\\r",
+            '\t' => output += "\\t",
+            c if (c as u32) < 0x20 => output += &format!("\\u{:04x}", c as u32),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+    output
+}
+
+fn render_json(fields: &[(&str, Field)]) -> String {
+    let members: Vec<String> = fields
+        .iter()
+        .map(|(name, field)| {
+            let value: String = match field {
+                Field::Null => "null".to_string(),
+                Field::Text(text) => json_string(text),
+                Field::Number(number) => number.to_string(),
+                Field::List(items) => format!("[{}]", items.iter().map(|item| json_string(item)).collect::<Vec<String>>().join(",")),
+            };
+            format!("{}:{}", json_string(name), value)
+        })
+        .collect();
+
+    format!("{{{}}}", members.join(","))
+}
+
+// Missing components are shown as "-", so that they differ from empty ones, which are quoted
+// like every other string.
+fn render_table(fields: &[(&str, Field)]) -> String {
+    let width: usize = fields.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    let lines: Vec<String> = fields
+        .iter()
+        .map(|(name, field)| {
+            let value: String = match field {
+                Field::Null => "-".to_string(),
+                Field::Text(text) => format!("{:?}", text),
+                Field::Number(number) => number.to_string(),
+                Field::List(items) => format!("{:?}", items),
+            };
+            format!("{:width$}  {}", name, value, width = width)
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+fn main() -> ExitCode {
+    let args: Args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Err(error) => {
+            eprintln!("resloc: {}\n\n{}", error, USAGE);
+            return ExitCode::from(2);
+        },
+    };
+
+    let options: ParseOptions = ParseOptions::new().strict(args.strict);
+
+    let base: Option<URL> = match &args.base {
+        Some(base) => match URL::parse_with_options(base, None, &options) {
+            Ok(base) => Some(base),
+            Err(error) => {
+                match error.diagnostic() {
+                    Some(diagnostic) => eprintln!("resloc: invalid base URL\n{}", diagnostic.render(base)),
+                    None => eprintln!("resloc: invalid base URL\n{}\n{}", error_message(&error), base),
+                }
+                return ExitCode::from(2);
+            },
+        },
+        None => None,
+    };
+
+    let inputs: Box<dyn Iterator<Item = String>> = match args.inputs.is_empty() {
+        true => Box::new(io::stdin().lock().lines().map_while(Result::ok).filter(|line| !line.trim().is_empty())),
+        false => Box::new(args.inputs.into_iter()),
+    };
+
+    let mut failed: bool = false;
+    for (index, input) in inputs.enumerate() {
+        let url: URL = match URL::parse_with_options(&input, base.as_ref(), &options) {
+            Ok(url) => url,
+            Err(error) => {
+                failed = true;
+                match error.diagnostic() {
+                    Some(diagnostic) => eprintln!("{}", diagnostic.render(&input)),
+                    None => eprintln!("{}\n{}", error_message(&error), input),
+                }
+
+                if args.json {
+                    println!("{{\"input\":{},\"error\":{}}}", json_string(&input), json_string(&error_message(&error)));
+                }
+                continue;
+            },
+        };
+
+        let fields = describe(&url);
+        if args.json {
+            println!("{}", render_json(&fields));
+        } else {
+            if index > 0 {
+                println!();
+            }
+            println!("{}", render_table(&fields));
+        }
+    }
+
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Option<Args>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&["--strict", "--base", "http://a/", "b", "--json", "--", "--c"]).unwrap().unwrap();
+        assert!(parsed.strict && parsed.json);
+        assert_eq!(parsed.base.as_deref(), Some("http://a/"));
+        assert_eq!(parsed.inputs, vec!["b", "--c"]);
+
+        assert_eq!(args(&["--base=http://a/"]).unwrap().unwrap().base.as_deref(), Some("http://a/"));
+        assert!(args(&["-h", "x"]).unwrap().is_none());
+        assert!(args(&["--base"]).is_err());
+        assert!(args(&["--verbose"]).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let url = URL::parse("https://user@www.example.co.uk:8443/a%20b/?q=\"1\"#").unwrap();
+        let fields = describe(&url);
+
+        assert_eq!(
+            render_json(&fields),
+            concat!(
+                "{\"href\":\"https://user@www.example.co.uk:8443/a%20b/?q=%221%22#\",\"scheme\":\"https\",",
+                "\"username\":\"user\",\"password\":\"\",\"host\":\"www.example.co.uk\",\"host_type\":\"domain\",",
+                "\"port\":8443,\"path\":\"/a%20b/\",\"path_segments\":[\"a b\",\"\"],\"query\":\"q=%221%22\",",
+                "\"fragment\":\"\",\"origin\":\"https://www.example.co.uk:8443\",\"public_suffix\":\"co.uk\",",
+                "\"registrable_domain\":\"example.co.uk\"}",
+            )
+        );
+
+        let table = render_table(&describe(&URL::parse("mailto:a@b").unwrap()));
+        assert!(table.contains("\nhost                -\n"));
+        assert!(table.contains("\npath                \"a@b\"\n"));
+        assert!(table.contains("\norigin              \"null\"\n"));
+        assert_eq!(json_string("a\"\\\u{1}\n"), "\"a\\\"\\\\\\u0001\\n\"");
+    }
+}
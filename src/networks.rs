@@ -0,0 +1,370 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::domains;
+use crate::errors::IpNetworkError;
+use crate::hosts::parse_ip_address;
+use crate::types::types::{Host, HostType, IPAddress, IpNetwork, IpRangeSet, Ipv6Address, Ipv6Pieces};
+
+// `IPAddress::IPv6` keeps the first piece in the low bits, so prefixes are applied to this
+// numeric value instead, where "fd00::" is greater than "::1".
+fn ipv6_to_numeric(address: u128) -> u128 {
+    Ipv6Pieces::from(Ipv6Address(address))
+        .iter()
+        .fold(0, |numeric, piece| numeric << 16 | *piece as u128)
+}
+
+fn numeric_to_ipv6(numeric: u128) -> u128 {
+    let mut pieces: Ipv6Pieces = [0_u16; 8];
+    for (index, piece) in pieces.iter_mut().enumerate() {
+        *piece = (numeric >> ((7 - index) * 16) & 0xFFFF) as u16;
+    }
+
+    Ipv6Address::from(pieces).0
+}
+
+// The mask of host bits, i.e. the bits not covered by a prefix of `prefix_len` out of `bits`.
+fn host_mask(prefix_len: u8, bits: u32) -> u128 {
+    u128::MAX.checked_shr(128 - bits + prefix_len as u32).unwrap_or(0)
+}
+
+impl IPAddress {
+    // Reference: https://www.rfc-editor.org/rfc/rfc4291#section-2.5.5.2
+    // The IPv4 address embedded in an IPv4-mapped IPv6 address such as "::ffff:192.0.2.1".
+    pub fn to_ipv4_mapped(&self) -> Option<IPAddress> {
+        let numeric: u128 = match self {
+            IPAddress::IPv4(_) => return None,
+            IPAddress::IPv6(address) => ipv6_to_numeric(*address),
+        };
+
+        if numeric >> 32 != 0xFFFF {
+            return None;
+        }
+
+        Some(IPAddress::IPv4(numeric as u32))
+    }
+}
+
+impl IpNetwork {
+    pub fn new(address: IPAddress, prefix_len: u8) -> Result<Self, IpNetworkError> {
+        let (numeric, bits): (u128, u32) = match address {
+            IPAddress::IPv4(address) => (address as u128, 32),
+            IPAddress::IPv6(address) => (ipv6_to_numeric(address), 128),
+        };
+
+        if prefix_len as u32 > bits {
+            return Err(IpNetworkError::InvalidPrefixLength);
+        }
+
+        if numeric & host_mask(prefix_len, bits) != 0 {
+            return Err(IpNetworkError::HostBitsSet);
+        }
+
+        Ok(IpNetwork { address, prefix_len })
+    }
+
+    pub fn address(&self) -> &IPAddress {
+        &self.address
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    fn ipv4_range(&self) -> Option<(u32, u32)> {
+        let address: u32 = match self.address {
+            IPAddress::IPv4(address) => address,
+            IPAddress::IPv6(_) => return None,
+        };
+
+        Some((address, address | host_mask(self.prefix_len, 32) as u32))
+    }
+
+    fn ipv6_range(&self) -> Option<(u128, u128)> {
+        let numeric: u128 = match self.address {
+            IPAddress::IPv4(_) => return None,
+            IPAddress::IPv6(address) => ipv6_to_numeric(address),
+        };
+
+        Some((numeric, numeric | host_mask(self.prefix_len, 128)))
+    }
+
+    // Addresses only match networks of their own family, so "::ffff:10.0.0.1" is not in
+    // "10.0.0.0/8"; use `contains_host` to also match IPv4-mapped addresses.
+    pub fn contains(&self, address: &IPAddress) -> bool {
+        match address {
+            IPAddress::IPv4(address) => self.ipv4_range().is_some_and(|(first, last)| (first..=last).contains(address)),
+            IPAddress::IPv6(address) => {
+                let numeric: u128 = ipv6_to_numeric(*address);
+                self.ipv6_range().is_some_and(|(first, last)| (first..=last).contains(&numeric))
+            },
+        }
+    }
+
+    // With `ipv4_mapped`, an IPv4-mapped IPv6 host also matches the IPv4 network it embeds.
+    pub fn contains_host(&self, host: &Host, ipv4_mapped: bool) -> bool {
+        host_addresses(host, ipv4_mapped).iter().any(|address| self.contains(address))
+    }
+}
+
+fn host_addresses(host: &Host, ipv4_mapped: bool) -> Vec<IPAddress> {
+    let address: &IPAddress = match &host.host_type {
+        HostType::IPAddress(address) => address,
+        HostType::Domain | HostType::Opaque | HostType::Empty => return Vec::new(),
+    };
+
+    let mut addresses: Vec<IPAddress> = vec![address.clone()];
+    if ipv4_mapped {
+        addresses.extend(address.to_ipv4_mapped());
+    }
+
+    addresses
+}
+
+// Accepts "address/prefix-length", or a lone address as a network of just that address. The
+// IPv4 part must be dotted-decimal, and an IPv6 address may be given with or without brackets.
+impl FromStr for IpNetwork {
+    type Err = IpNetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len): (&str, Option<&str>) = match s.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s, None),
+        };
+
+        let address: IPAddress = match parse_ip_address(address) {
+            Some(address) => address,
+            None => return Err(IpNetworkError::InvalidAddress),
+        };
+
+        let prefix_len: u8 = match (prefix_len, &address) {
+            (None, IPAddress::IPv4(_)) => 32,
+            (None, IPAddress::IPv6(_)) => 128,
+            (Some(prefix_len), _) => {
+                if prefix_len.is_empty() || prefix_len.len() > 3 || !prefix_len.bytes().all(|byte| byte.is_ascii_digit()) {
+                    return Err(IpNetworkError::InvalidPrefixLength);
+                }
+
+                prefix_len.parse::<u8>().map_err(|_| IpNetworkError::InvalidPrefixLength)?
+            },
+        };
+
+        IpNetwork::new(address, prefix_len)
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address: String = match self.address {
+            IPAddress::IPv4(address) => domains::ipv4_serializer(address),
+            IPAddress::IPv6(address) => domains::ipv6_serializer(Ipv6Pieces::from(Ipv6Address(address))),
+        };
+
+        write!(f, "{}/{}", address, self.prefix_len)
+    }
+}
+
+// Inserts [first, last] into sorted, disjoint `ranges`, merging it with every range it overlaps
+// or touches.
+fn insert_range<T: Copy + Ord>(ranges: &mut Vec<(T, T)>, (mut first, mut last): (T, T), successor: fn(T) -> Option<T>) {
+    let start: usize = ranges.partition_point(|&(_, range_last)| successor(range_last).is_some_and(|next| next < first));
+    let mut end: usize = start;
+
+    while end < ranges.len() && successor(last).is_none_or(|next| ranges[end].0 <= next) {
+        first = first.min(ranges[end].0);
+        last = last.max(ranges[end].1);
+        end += 1;
+    }
+
+    ranges.splice(start..end, [(first, last)]);
+}
+
+fn contains_value<T: Copy + Ord>(ranges: &[(T, T)], value: T) -> bool {
+    let index: usize = ranges.partition_point(|&(_, last)| last < value);
+    ranges.get(index).is_some_and(|&(first, _)| first <= value)
+}
+
+impl IpRangeSet {
+    pub fn new() -> Self {
+        IpRangeSet::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ipv4.is_empty() && self.ipv6.is_empty()
+    }
+
+    pub fn insert(&mut self, network: &IpNetwork) {
+        if let Some(range) = network.ipv4_range() {
+            insert_range(&mut self.ipv4, range, |last| last.checked_add(1));
+        }
+
+        if let Some(range) = network.ipv6_range() {
+            insert_range(&mut self.ipv6, range, |last| last.checked_add(1));
+        }
+    }
+
+    // The smallest list of networks covering exactly the addresses in the set, IPv4 first.
+    pub fn networks(&self) -> Vec<IpNetwork> {
+        let mut networks: Vec<IpNetwork> = Vec::new();
+
+        for &(first, last) in &self.ipv4 {
+            split_range(first as u128, last as u128, 32, &mut |start, prefix_len| {
+                networks.push(IpNetwork { address: IPAddress::IPv4(start as u32), prefix_len });
+            });
+        }
+
+        for &(first, last) in &self.ipv6 {
+            split_range(first, last, 128, &mut |start, prefix_len| {
+                networks.push(IpNetwork { address: IPAddress::IPv6(numeric_to_ipv6(start)), prefix_len });
+            });
+        }
+
+        networks
+    }
+
+    pub fn contains(&self, address: &IPAddress) -> bool {
+        match address {
+            IPAddress::IPv4(address) => contains_value(&self.ipv4, *address),
+            IPAddress::IPv6(address) => contains_value(&self.ipv6, ipv6_to_numeric(*address)),
+        }
+    }
+
+    // With `ipv4_mapped`, an IPv4-mapped IPv6 host also matches the IPv4 ranges.
+    pub fn contains_host(&self, host: &Host, ipv4_mapped: bool) -> bool {
+        host_addresses(host, ipv4_mapped).iter().any(|address| self.contains(address))
+    }
+}
+
+// Splits [first, last] into the largest aligned blocks, from the lowest address up.
+fn split_range(mut first: u128, last: u128, bits: u32, push: &mut dyn FnMut(u128, u8)) {
+    loop {
+        let mut prefix_len: u8 = (bits - first.trailing_zeros().min(bits)) as u8;
+        while first | host_mask(prefix_len, bits) > last {
+            prefix_len += 1;
+        }
+
+        push(first, prefix_len);
+
+        let block_last: u128 = first | host_mask(prefix_len, bits);
+        if block_last == last {
+            return;
+        }
+        first = block_last + 1;
+    }
+}
+
+impl FromIterator<IpNetwork> for IpRangeSet {
+    fn from_iter<I: IntoIterator<Item = IpNetwork>>(iter: I) -> Self {
+        let mut set = IpRangeSet::new();
+        for network in iter {
+            set.insert(&network);
+        }
+
+        set
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::host_parser;
+
+    fn network(input: &str) -> IpNetwork {
+        input.parse().unwrap()
+    }
+
+    fn address(input: &str) -> IPAddress {
+        parse_ip_address(input).unwrap()
+    }
+
+    fn host(input: &str) -> Host {
+        host_parser(input, false).unwrap()
+    }
+
+    #[test]
+    fn test_parse_network() {
+        assert_eq!(network("10.0.0.0/8"), IpNetwork { address: IPAddress::IPv4(0x0A000000), prefix_len: 8 });
+        assert_eq!(network("192.0.2.1"), IpNetwork { address: IPAddress::IPv4(0xC0000201), prefix_len: 32 });
+        assert_eq!(network("0.0.0.0/0").prefix_len(), 0);
+        assert_eq!(network("[fd00::]/8"), network("fd00::/8"));
+        assert_eq!(network("::/0").prefix_len(), 0);
+        assert_eq!(network("2001:db8::1").prefix_len(), 128);
+
+        assert_eq!(network("10.0.0.0/8").to_string(), "10.0.0.0/8");
+        assert_eq!(network("2001:DB8:0::/32").to_string(), "2001:db8::/32");
+
+        assert_eq!("10.0.0.1/8".parse::<IpNetwork>(), Err(IpNetworkError::HostBitsSet));
+        assert_eq!("fd00::1/8".parse::<IpNetwork>(), Err(IpNetworkError::HostBitsSet));
+        assert_eq!("10.0.0.0/33".parse::<IpNetwork>(), Err(IpNetworkError::InvalidPrefixLength));
+        assert_eq!("::/129".parse::<IpNetwork>(), Err(IpNetworkError::InvalidPrefixLength));
+        assert_eq!("10.0.0.0/".parse::<IpNetwork>(), Err(IpNetworkError::InvalidPrefixLength));
+        assert_eq!("10.0.0.0/+8".parse::<IpNetwork>(), Err(IpNetworkError::InvalidPrefixLength));
+        assert_eq!("10/8".parse::<IpNetwork>(), Err(IpNetworkError::InvalidAddress));
+        assert_eq!("example.com/8".parse::<IpNetwork>(), Err(IpNetworkError::InvalidAddress));
+    }
+
+    #[test]
+    fn test_network_contains() {
+        let private = network("10.0.0.0/8");
+        assert!(private.contains(&address("10.0.0.0")));
+        assert!(private.contains(&address("10.255.255.255")));
+        assert!(!private.contains(&address("11.0.0.0")));
+        assert!(!private.contains(&address("::ffff:10.0.0.1")));
+
+        let unique_local = network("fd00::/8");
+        assert!(unique_local.contains(&address("fd12:3456::1")));
+        assert!(!unique_local.contains(&address("fe80::1")));
+        assert!(!unique_local.contains(&address("::fd00")));
+
+        assert!(network("0.0.0.0/0").contains(&address("255.255.255.255")));
+        assert!(network("::/0").contains(&address("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff")));
+        assert!(!network("::/0").contains(&address("1.2.3.4")));
+
+        assert!(private.contains_host(&host("0xA.1"), false));
+        assert!(!private.contains_host(&host("[::ffff:10.0.0.1]"), false));
+        assert!(private.contains_host(&host("[::ffff:10.0.0.1]"), true));
+        assert!(!private.contains_host(&host("[::10.0.0.1]"), true));
+        assert!(!private.contains_host(&host("example.com"), true));
+
+        assert_eq!(address("::ffff:192.0.2.1").to_ipv4_mapped(), Some(address("192.0.2.1")));
+        assert_eq!(address("192.0.2.1").to_ipv4_mapped(), None);
+    }
+
+    #[test]
+    fn test_range_set() {
+        let set: IpRangeSet = ["10.0.0.0/8", "10.1.0.0/16", "11.0.0.0/8", "192.168.0.0/24", "192.168.2.0/24", "fd00::/8", "::1"]
+            .iter()
+            .map(|input| network(input))
+            .collect();
+
+        assert_eq!(set.ipv4, vec![(0x0A000000, 0x0BFFFFFF), (0xC0A80000, 0xC0A800FF), (0xC0A80200, 0xC0A802FF)]);
+        assert_eq!(set.ipv6.len(), 2);
+
+        assert!(set.contains(&address("10.0.0.0")));
+        assert!(set.contains(&address("11.255.255.255")));
+        assert!(!set.contains(&address("12.0.0.0")));
+        assert!(!set.contains(&address("192.168.1.1")));
+        assert!(set.contains(&address("192.168.2.255")));
+        assert!(set.contains(&address("::1")));
+        assert!(!set.contains(&address("::2")));
+        assert!(set.contains(&address("fdff:ffff::")));
+        assert!(set.contains_host(&host("[::ffff:11.0.0.1]"), true));
+        assert!(!set.contains_host(&host("[::ffff:11.0.0.1]"), false));
+
+        let networks: Vec<String> = set.networks().iter().map(|network| network.to_string()).collect();
+        assert_eq!(networks, vec!["10.0.0.0/7", "192.168.0.0/24", "192.168.2.0/24", "::1/128", "fd00::/8"]);
+
+        let mut set: IpRangeSet = IpRangeSet::new();
+        assert!(set.is_empty());
+        set.insert(&network("192.168.1.0/24"));
+        set.insert(&network("192.168.0.0/24"));
+        set.insert(&network("192.168.3.0/24"));
+        set.insert(&network("192.168.2.0/24"));
+        assert_eq!(set.ipv4, vec![(0xC0A80000, 0xC0A803FF)]);
+
+        set.insert(&network("0.0.0.0/0"));
+        set.insert(&network("::/0"));
+        assert_eq!(set.networks(), vec![network("0.0.0.0/0"), network("::/0")]);
+    }
+}
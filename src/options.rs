@@ -0,0 +1,101 @@
+use crate::types::types::{ParseOptions, ResourceLimits};
+
+// Reference: https://url.spec.whatwg.org/#concept-domain-to-ascii
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            check_hyphens: false,
+            check_bidi: true,
+            check_joiners: true,
+            use_std3_ascii_rules: false,
+            transitional_processing: false,
+            verify_dns_length: false,
+            strict: false,
+            limits: ResourceLimits::default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        ParseOptions::default()
+    }
+
+    // The URL Standard's beStrict flag, which turns on both CheckHyphens and VerifyDnsLength.
+    pub fn be_strict(mut self, value: bool) -> Self {
+        self.check_hyphens = value;
+        self.verify_dns_length = value;
+        self
+    }
+
+    pub fn check_hyphens(mut self, value: bool) -> Self {
+        self.check_hyphens = value;
+        self
+    }
+
+    pub fn check_bidi(mut self, value: bool) -> Self {
+        self.check_bidi = value;
+        self
+    }
+
+    pub fn check_joiners(mut self, value: bool) -> Self {
+        self.check_joiners = value;
+        self
+    }
+
+    pub fn use_std3_ascii_rules(mut self, value: bool) -> Self {
+        self.use_std3_ascii_rules = value;
+        self
+    }
+
+    pub fn transitional_processing(mut self, value: bool) -> Self {
+        self.transitional_processing = value;
+        self
+    }
+
+    pub fn verify_dns_length(mut self, value: bool) -> Self {
+        self.verify_dns_length = value;
+        self
+    }
+
+    // Fails on every validation error, including those the URL Standard recovers from, such as
+    // a U+005C(\) in place of a U+002F(/) or credentials in the URL.
+    pub fn strict(mut self, value: bool) -> Self {
+        self.strict = value;
+        self
+    }
+
+    // The limits below fail parsing with `ReslocError::LimitExceeded` and are meant for input
+    // from untrusted sources. Lengths are in bytes of UTF-8.
+
+    // Checked before anything else, including the removal of leading and trailing spaces.
+    pub fn max_input_length(mut self, value: usize) -> Self {
+        self.limits.max_input_length = Some(value);
+        self
+    }
+
+    // Counts every segment in the input, so "/a/../b" has three even though the path ends up
+    // with one. This also bounds runs of "..".
+    pub fn max_path_segments(mut self, value: usize) -> Self {
+        self.limits.max_path_segments = Some(value);
+        self
+    }
+
+    // Checked before the host is parsed, so that a long host never reaches IDNA processing.
+    pub fn max_host_length(mut self, value: usize) -> Self {
+        self.limits.max_host_length = Some(value);
+        self
+    }
+
+    // The length of the query before it is percent-encoded.
+    pub fn max_query_length(mut self, value: usize) -> Self {
+        self.limits.max_query_length = Some(value);
+        self
+    }
+
+    // The number of non-empty name-value pairs separated by U+0026(&) in the query.
+    pub fn max_search_params(mut self, value: usize) -> Self {
+        self.limits.max_search_params = Some(value);
+        self
+    }
+}
@@ -0,0 +1,121 @@
+// Reference: https://url.spec.whatwg.org/#percent-encoded-bytes
+
+// Reference: https://url.spec.whatwg.org/#c0-control-percent-encode-set
+#[derive(Clone, Copy, Debug)]
+pub enum PercentEncodeSet {
+    C0Control,
+    Fragment,
+    Query,
+    SpecialQuery,
+    Path,
+    Userinfo,
+    Component,
+    ApplicationXWwwFormUrlencoded,
+}
+
+impl PercentEncodeSet {
+    // Each set is a superset of the one it refers to, as in the spec.
+    pub fn contains(&self, byte: u8) -> bool {
+        match self {
+            Self::C0Control => !(0x20..=0x7E).contains(&byte),
+            Self::Fragment => Self::C0Control.contains(byte) || b" \"<>`".contains(&byte),
+            Self::Query => Self::C0Control.contains(byte) || b" \"#<>".contains(&byte),
+            Self::SpecialQuery => Self::Query.contains(byte) || byte == b'\'',
+            Self::Path => Self::Query.contains(byte) || b"?^`{}".contains(&byte),
+            Self::Userinfo => Self::Path.contains(byte) || b"/:;=@[\\]|".contains(&byte),
+            Self::Component => Self::Userinfo.contains(byte) || b"$%&+,".contains(&byte),
+            Self::ApplicationXWwwFormUrlencoded => Self::Component.contains(byte) || b"!'()~".contains(&byte),
+        }
+    }
+}
+
+// Reference: https://url.spec.whatwg.org/#percent-encode
+pub fn percent_encode_byte(byte: u8) -> String {
+    format!("%{:02X}", byte)
+}
+
+pub fn percent_encode_bytes(input: &[u8], percent_encode_set: PercentEncodeSet) -> String {
+    let mut output: String = String::with_capacity(input.len());
+
+    for byte in input {
+        if percent_encode_set.contains(*byte) {
+            output += &percent_encode_byte(*byte);
+        } else {
+            output.push(*byte as char);
+        }
+    }
+
+    output
+}
+
+// Reference: https://url.spec.whatwg.org/#utf-8-percent-encode
+pub fn utf8_percent_encode(c: char, percent_encode_set: PercentEncodeSet) -> String {
+    let mut bytes = [0_u8; 4];
+    percent_encode_bytes(c.encode_utf8(&mut bytes).as_bytes(), percent_encode_set)
+}
+
+// Reference: https://url.spec.whatwg.org/#string-utf-8-percent-encode
+pub fn utf8_percent_encode_string(input: &str, percent_encode_set: PercentEncodeSet) -> String {
+    percent_encode_bytes(input.as_bytes(), percent_encode_set)
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'..=b'9' => Some(byte - b'0'),
+        b'a'..=b'f' => Some(byte - b'a' + 10),
+        b'A'..=b'F' => Some(byte - b'A' + 10),
+        _ => None,
+    }
+}
+
+// Reference: https://url.spec.whatwg.org/#percent-decode
+pub fn percent_decode(input: &[u8]) -> Vec<u8> {
+    let mut output: Vec<u8> = Vec::with_capacity(input.len());
+    let mut pointer: usize = 0;
+
+    while pointer < input.len() {
+        let byte = input[pointer];
+
+        if byte == b'%' && pointer + 2 < input.len() {
+            let high = hex_value(input[pointer + 1]);
+            let low = hex_value(input[pointer + 2]);
+
+            if let (Some(high), Some(low)) = (high, low) {
+                output.push(high * 0x10 + low);
+                pointer += 3;
+                continue;
+            }
+        }
+
+        output.push(byte);
+        pointer += 1;
+    }
+
+    output
+}
+
+// Reference: https://url.spec.whatwg.org/#string-percent-decode
+pub fn percent_decode_string(input: &str) -> Vec<u8> {
+    percent_decode(input.as_bytes())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_encode() {
+        assert_eq!(utf8_percent_encode_string("a b#c?d", PercentEncodeSet::Query), "a%20b%23c?d");
+        assert_eq!(utf8_percent_encode_string("a b#c?d", PercentEncodeSet::Path), "a%20b%23c%3Fd");
+        assert_eq!(utf8_percent_encode_string("\u{203D}%", PercentEncodeSet::C0Control), "%E2%80%BD%");
+        assert_eq!(utf8_percent_encode_string("'", PercentEncodeSet::SpecialQuery), "%27");
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode_string("%25%s%1G"), b"%%s%1G".to_vec());
+        assert_eq!(percent_decode_string("a%20b%2"), b"a b%2".to_vec());
+        assert_eq!(percent_decode_string("%E2%80%BD"), "\u{203D}".as_bytes().to_vec());
+    }
+}
@@ -0,0 +1,124 @@
+// The layout of the public suffix trie, shared by build.rs, which compiles data/psl.dat into
+// static tables, and by src/public_suffix.rs, which builds the same tables for lists loaded at
+// runtime, so both are looked up by the same matcher.
+use std::collections::{BTreeMap, VecDeque};
+
+pub(crate) const LEAF_ICANN: u8 = 1;
+pub(crate) const LEAF_PRIVATE: u8 = 2;
+pub(crate) const LEAF_EXCEPTION: u8 = 4;
+
+// A node of the trie. Labels are slices of the trie's label string, and the children of a node
+// are the contiguous range of nodes starting at `children_start`, sorted by label length and
+// then by label bytes.
+pub(crate) struct PslNode {
+    // The first 8 bytes of the label, big-endian and zero-padded, which orders labels of equal
+    // length the same way as their bytes do.
+    pub(crate) label_prefix: u64,
+    pub(crate) label_start: u32,
+    pub(crate) label_len: u8,
+    pub(crate) children_start: u32,
+    pub(crate) children_len: u32,
+    pub(crate) leaf: u8,
+}
+
+pub(crate) fn label_prefix(label: &[u8]) -> u64 {
+    let mut prefix: [u8; 8] = [0; 8];
+    let len: usize = label.len().min(8);
+    prefix[..len].copy_from_slice(&label[..len]);
+    u64::from_be_bytes(prefix)
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: BTreeMap<String, TrieNode>,
+    leaf: u8,
+}
+
+impl TrieNode {
+    // Lookups binary search children by length first, so most comparisons never look at the
+    // label bytes.
+    fn sorted_children(&self) -> Vec<(&str, &TrieNode)> {
+        let mut children: Vec<(&str, &TrieNode)> = self.children.iter().map(|(label, child)| (label.as_str(), child)).collect();
+        children.sort_by_key(|(label, _)| (label.len(), label.as_bytes()));
+        children
+    }
+
+    fn insert(&mut self, rule: &str, leaf: u8) {
+        let mut node: &mut TrieNode = self;
+        for label in rule.rsplit('.') {
+            node = node.children.entry(label.to_string()).or_default();
+        }
+        node.leaf = leaf;
+    }
+}
+
+// Mirrors the section handling of the publicsuffix crate: rules outside of the ICANN and PRIVATE
+// sections are ignored, and U-label rules are stored in their A-label form. Fails with the rule
+// that is not a valid domain or has a label longer than 255 bytes.
+fn parse_rules(input: &str) -> Result<TrieNode, String> {
+    let mut root: TrieNode = TrieNode::default();
+    let mut section: Option<u8> = None;
+
+    for line in input.lines() {
+        if line.contains("BEGIN ICANN DOMAINS") {
+            section = Some(LEAF_ICANN);
+            continue;
+        } else if line.contains("BEGIN PRIVATE DOMAINS") {
+            section = Some(LEAF_PRIVATE);
+            continue;
+        } else if line.starts_with("//") {
+            continue;
+        }
+
+        let (section, rule) = match (section, line.split_whitespace().next()) {
+            (Some(section), Some(rule)) => (section, rule),
+            _ => continue,
+        };
+
+        let (rule, leaf) = match rule.strip_prefix('!') {
+            Some(rule) => (rule, section | LEAF_EXCEPTION),
+            None => (rule, section),
+        };
+
+        let ascii_rule: String = match idna::domain_to_ascii(rule) {
+            Ok(ascii_rule) if ascii_rule.split('.').all(|label| label.len() <= u8::MAX as usize) => ascii_rule,
+            _ => return Err(rule.to_string()),
+        };
+        root.insert(&ascii_rule, leaf);
+    }
+
+    Ok(root)
+}
+
+// Returns the label string and the nodes of the trie for the rules in `input`, with the root
+// node first. Nodes are laid out breadth first, so every node's children are contiguous.
+pub(crate) fn build_trie(input: &str) -> Result<(String, Vec<PslNode>), String> {
+    let root: TrieNode = parse_rules(input)?;
+
+    let mut labels: String = String::new();
+    let mut label_offsets: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut nodes: Vec<PslNode> = Vec::new();
+    let mut node_count: usize = 1;
+    let mut queue: VecDeque<(&str, &TrieNode)> = VecDeque::from([("", &root)]);
+
+    while let Some((label, node)) = queue.pop_front() {
+        let label_start: usize = *label_offsets.entry(label).or_insert_with(|| {
+            labels += label;
+            labels.len() - label.len()
+        });
+
+        nodes.push(PslNode {
+            label_prefix: label_prefix(label.as_bytes()),
+            label_start: label_start as u32,
+            label_len: label.len() as u8,
+            children_start: node_count as u32,
+            children_len: node.children.len() as u32,
+            leaf: node.leaf,
+        });
+
+        node_count += node.children.len();
+        queue.extend(node.sorted_children());
+    }
+
+    Ok((labels, nodes))
+}
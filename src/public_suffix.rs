@@ -0,0 +1,337 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::errors::PublicSuffixListError;
+use crate::psl_trie::{build_trie, label_prefix, PslNode, LEAF_EXCEPTION, LEAF_ICANN, LEAF_PRIVATE};
+use crate::types::types::{PublicSuffixList, PublicSuffixRules, PublicSuffixSection, SuffixMatch};
+
+lazy_static::lazy_static! {
+    static ref DEFAULT_LIST: DefaultList = DefaultList::new(PublicSuffixList::builtin());
+}
+
+// Readers clone the Arc and release the lock straight away, so replacing the list never waits on
+// a lookup and lookups in flight keep using the list they started with.
+struct DefaultList(RwLock<Arc<PublicSuffixList>>);
+
+impl DefaultList {
+    fn new(list: PublicSuffixList) -> DefaultList {
+        DefaultList(RwLock::new(Arc::new(list)))
+    }
+
+    fn get(&self) -> Arc<PublicSuffixList> {
+        let list = self.0.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::clone(&list)
+    }
+
+    fn replace(&self, list: PublicSuffixList) -> Arc<PublicSuffixList> {
+        let mut current = self.0.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::mem::replace(&mut *current, Arc::new(list))
+    }
+}
+
+impl PublicSuffixList {
+    // The list vendored at data/psl.dat when this crate was built, compiled into a static trie.
+    pub fn builtin() -> PublicSuffixList {
+        PublicSuffixList { rules: PublicSuffixRules::Compiled }
+    }
+
+    // The list is validated by the publicsuffix crate, but looked up through the same trie as the
+    // built-in list, so both resolve every rule the same way.
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicSuffixList, PublicSuffixListError> {
+        publicsuffix::List::from_bytes(bytes).map_err(PublicSuffixListError::InvalidList)?;
+        let (labels, nodes) = build_trie(&String::from_utf8_lossy(bytes)).map_err(PublicSuffixListError::InvalidRule)?;
+        Ok(PublicSuffixList { rules: PublicSuffixRules::Parsed { labels, nodes } })
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<PublicSuffixList, PublicSuffixListError> {
+        let mut bytes: Vec<u8> = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(PublicSuffixListError::Io)?;
+        PublicSuffixList::from_bytes(&bytes)
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<PublicSuffixList, PublicSuffixListError> {
+        let file = File::open(path).map_err(PublicSuffixListError::Io)?;
+        PublicSuffixList::from_reader(file)
+    }
+
+    // The list used by lookups that are not given one explicitly.
+    pub fn default_list() -> Arc<PublicSuffixList> {
+        DEFAULT_LIST.get()
+    }
+
+    // Atomically replaces the default list and returns the previous one.
+    pub fn set_default_list(list: PublicSuffixList) -> Arc<PublicSuffixList> {
+        DEFAULT_LIST.replace(list)
+    }
+}
+
+// The tables compiled from data/psl.dat by build.rs.
+include!(concat!(env!("OUT_DIR"), "/psl_trie.rs"));
+
+// A trie laid out by `build_trie`: either the static tables compiled by build.rs or the tables
+// of a list loaded at runtime.
+#[derive(Clone, Copy)]
+struct PslTrie<'a> {
+    labels: &'a str,
+    nodes: &'a [PslNode],
+}
+
+impl<'a> PslTrie<'a> {
+    fn label(&self, node: &PslNode) -> &'a [u8] {
+        let start: usize = node.label_start as usize;
+        &self.labels.as_bytes()[start..start + node.label_len as usize]
+    }
+
+    // Labels up to 8 bytes long are told apart by their key alone, so the binary search never
+    // touches the label string and only longer labels sharing a key are compared byte by byte.
+    fn child(&self, node: &PslNode, label: &[u8]) -> Option<&'a PslNode> {
+        let start: usize = node.children_start as usize;
+        let children: &'a [PslNode] = &self.nodes[start..start + node.children_len as usize];
+        let key: u128 = ((label.len() as u128) << 64) | label_prefix(label) as u128;
+
+        let first: usize = children.partition_point(|child| child.key() < key);
+        children[first..]
+            .iter()
+            .take_while(|child| child.key() == key)
+            .find(|child| self.label(child) == label)
+    }
+}
+
+impl PslNode {
+    fn key(&self) -> u128 {
+        ((self.label_len as u128) << 64) | self.label_prefix as u128
+    }
+
+    fn section(&self, ignore_private: bool) -> Option<PublicSuffixSection> {
+        if self.leaf & LEAF_ICANN != 0 {
+            return Some(PublicSuffixSection::Icann);
+        } else if self.leaf & LEAF_PRIVATE != 0 && !ignore_private {
+            return Some(PublicSuffixSection::Private);
+        }
+
+        None
+    }
+}
+
+// The prevailing rule found so far, and whether it is an exception rule, which wins over any
+// normal rule.
+struct TrieMatch {
+    suffix: SuffixMatch,
+    is_exception: bool,
+}
+
+// Both the node of `label` itself and a "*" sibling can lead to the prevailing rule: with
+// "*.customer-oci.com" and "*.oci.customer-oci.com", "oci.customer-oci.com" is only matched by
+// the wildcard, so both branches are followed.
+fn match_labels<'a, I>(trie: PslTrie, node: &PslNode, mut labels: I, len_so_far: usize, ignore_private: bool, best: &mut TrieMatch)
+where
+    I: Iterator<Item = &'a [u8]> + Clone,
+{
+    let label: &[u8] = match labels.next() {
+        Some(label) => label,
+        None => return,
+    };
+
+    let len: usize = len_so_far + 1 + label.len();
+    let wildcard: Option<&PslNode> = match label {
+        b"*" => None,
+        _ => trie.child(node, b"*"),
+    };
+
+    for child in [trie.child(node, label), wildcard].into_iter().flatten() {
+        if let Some(section) = child.section(ignore_private) {
+            if child.leaf & LEAF_EXCEPTION != 0 {
+                if !best.is_exception {
+                    best.suffix = SuffixMatch { len: len_so_far, section: Some(section) };
+                    best.is_exception = true;
+                }
+                continue;
+            }
+
+            if !best.is_exception && len > best.suffix.len {
+                best.suffix = SuffixMatch { len, section: Some(section) };
+            }
+        }
+
+        match_labels(trie, child, labels.clone(), len, ignore_private, best);
+    }
+}
+
+// Reference: https://github.com/publicsuffix/list/wiki/Format#formal-algorithm
+// Walks the labels from the right without allocating. The longest matching rule wins unless an
+// exception rule matches.
+fn trie_suffix(trie: PslTrie, domain: &[u8], ignore_private: bool) -> SuffixMatch {
+    let mut labels = domain.rsplit(|byte| *byte == b'.');
+
+    let top_level_label: &[u8] = labels.next().unwrap_or(&[]);
+    let mut best = TrieMatch {
+        suffix: SuffixMatch { len: top_level_label.len(), section: None },
+        is_exception: false,
+    };
+
+    if let Some(node) = trie.child(&trie.nodes[0], top_level_label) {
+        best.suffix.section = node.section(ignore_private);
+        match_labels(trie, node, labels, top_level_label.len(), ignore_private, &mut best);
+    }
+
+    best.suffix
+}
+
+impl PublicSuffixList {
+    // Looks up `domain`, which must already be a lowercase ASCII domain without a trailing dot or
+    // empty labels, such as a domain host from the URL parser. Unlike `get_public_suffix`, this
+    // neither allocates nor runs domain to ASCII.
+    pub fn find(&self, domain: &[u8], ignore_private: bool) -> SuffixMatch {
+        let trie: PslTrie = match &self.rules {
+            PublicSuffixRules::Compiled => PslTrie { labels: PSL_LABELS, nodes: &PSL_NODES },
+            PublicSuffixRules::Parsed { labels, nodes } => PslTrie { labels, nodes },
+        };
+
+        trie_suffix(trie, domain, ignore_private)
+    }
+}
+
+impl FromStr for PublicSuffixList {
+    type Err = PublicSuffixListError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        PublicSuffixList::from_bytes(input.as_bytes())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hosts::{get_public_suffix, get_registrable_domain};
+    use crate::types::types::{Host, HostType, PublicSuffix};
+
+    fn domain(value: &str) -> Host {
+        Host::new(value.to_string(), HostType::Domain)
+    }
+
+    // Rules outside of a section are ignored, as in the upstream list format.
+    fn icann_rules(rules: &str) -> String {
+        format!("// ===BEGIN ICANN DOMAINS===\n{}// ===END ICANN DOMAINS===\n", rules)
+    }
+
+    #[test]
+    fn test_explicit_list() {
+        let list: PublicSuffixList = icann_rules("example\n").parse().unwrap();
+        assert_eq!(get_public_suffix(&domain("www.foo.example"), Some(&list), false).map(|suffix| suffix.value), Some("example".to_string()));
+        assert_eq!(get_registrable_domain(&domain("www.foo.example"), Some(&list), false), Some("foo.example".to_string()));
+
+        let list = PublicSuffixList::from_reader(icann_rules("com\nco.uk\n").as_bytes()).unwrap();
+        assert_eq!(get_registrable_domain(&domain("a.b.co.uk"), Some(&list), false), Some("b.co.uk".to_string()));
+
+        assert!(PublicSuffixList::from_bytes(b"\xFF").is_err());
+        assert!(PublicSuffixList::from_bytes(b"com\n").is_err());
+        assert!(matches!(PublicSuffixList::from_path("/nonexistent/psl.dat"), Err(PublicSuffixListError::Io(_))));
+        assert!(matches!(PublicSuffixList::from_bytes(icann_rules(&format!("{}.example\n", "a".repeat(300))).as_bytes()), Err(PublicSuffixListError::InvalidRule(_))));
+    }
+
+    #[test]
+    fn test_sections() {
+        let list: PublicSuffixList = (icann_rules("io\nuk\nco.uk\n") + "// ===BEGIN PRIVATE DOMAINS===\ngithub.io\n// ===END PRIVATE DOMAINS===\n").parse().unwrap();
+
+        let suffix = |host: &str, ignore_private: bool| get_public_suffix(&domain(host), Some(&list), ignore_private).unwrap();
+        let known = |value: &str, section: PublicSuffixSection| PublicSuffix { value: value.to_string(), section: Some(section), is_known: true };
+
+        assert_eq!(suffix("foo.github.io", false), known("github.io", PublicSuffixSection::Private));
+        assert_eq!(suffix("foo.github.io", true), known("io", PublicSuffixSection::Icann));
+        assert_eq!(suffix("foo.co.uk", false), known("co.uk", PublicSuffixSection::Icann));
+        assert_eq!(suffix("foo.co.uk", true), known("co.uk", PublicSuffixSection::Icann));
+        assert_eq!(suffix("foo.unlisted", false), PublicSuffix { value: "unlisted".to_string(), section: None, is_known: false });
+
+        assert_eq!(get_registrable_domain(&domain("foo.github.io"), Some(&list), false), Some("foo.github.io".to_string()));
+        assert_eq!(get_registrable_domain(&domain("foo.github.io"), Some(&list), true), Some("github.io".to_string()));
+        assert_eq!(get_registrable_domain(&domain("github.io"), Some(&list), false), None);
+    }
+
+    // "*.firenet.ch" and "*.svc.firenet.ch" are both rules, so "svc" has a node of its own that is
+    // not a leaf and the wildcard above it still has to match, in lists loaded at runtime too.
+    #[test]
+    fn test_wildcard_with_explicit_sibling() {
+        let lists = [PublicSuffixList::builtin(), PublicSuffixList::from_bytes(include_bytes!("../data/psl.dat")).unwrap()];
+
+        for list in &lists {
+            let suffix = |host: &str| get_public_suffix(&domain(host), Some(list), false).unwrap();
+            let private = |value: &str| PublicSuffix { value: value.to_string(), section: Some(PublicSuffixSection::Private), is_known: true };
+
+            assert_eq!(suffix("svc.firenet.ch"), private("svc.firenet.ch"));
+            assert_eq!(suffix("a.svc.firenet.ch"), private("a.svc.firenet.ch"));
+            assert_eq!(suffix("a.b.svc.firenet.ch"), private("b.svc.firenet.ch"));
+            assert_eq!(suffix("a.other.firenet.ch"), private("other.firenet.ch"));
+            assert_eq!(suffix("oci.customer-oci.com"), private("oci.customer-oci.com"));
+            assert_eq!(suffix("x.oci.customer-oci.com"), private("x.oci.customer-oci.com"));
+
+            assert_eq!(get_registrable_domain(&domain("svc.firenet.ch"), Some(list), false), None);
+            assert_eq!(get_registrable_domain(&domain("a.b.svc.firenet.ch"), Some(list), false), Some("a.b.svc.firenet.ch".to_string()));
+        }
+    }
+
+    // Every rule of the vendored list has to resolve the same way through the compiled trie as
+    // through the trie built at runtime: the rule itself with a couple of labels in front, the
+    // labels a wildcard rule matches, and the hosts an exception rule carves out.
+    #[test]
+    fn test_compiled_matches_parsed() {
+        let compiled = PublicSuffixList::builtin();
+        let parsed = PublicSuffixList::from_bytes(include_bytes!("../data/psl.dat")).unwrap();
+
+        let mut domains: Vec<String> = ["com", "unlisted", "a.unlisted", "svc.firenet.ch", "a.other.firenet.ch", "oci.customer-oci.com"]
+            .iter()
+            .map(|domain| domain.to_string())
+            .collect();
+
+        for line in include_str!("../data/psl.dat").lines() {
+            let rule: &str = match line.split_whitespace().next() {
+                Some(rule) if !rule.starts_with("//") => rule,
+                _ => continue,
+            };
+
+            let ascii_rule: String = match idna::domain_to_ascii(rule.trim_start_matches('!')) {
+                Ok(ascii_rule) => ascii_rule,
+                Err(_) => continue,
+            };
+
+            if let Some(parent) = ascii_rule.strip_prefix("*.") {
+                domains.extend([parent.to_string(), format!("other.{}", parent), format!("a.other.{}", parent)]);
+            } else if rule.starts_with('!') {
+                domains.extend([ascii_rule.clone(), format!("a.{}", ascii_rule)]);
+            } else {
+                domains.extend([ascii_rule.clone(), format!("b.a.{}", ascii_rule)]);
+            }
+        }
+
+        for domain in &domains {
+            for ignore_private in [false, true] {
+                assert_eq!(
+                    compiled.find(domain.as_bytes(), ignore_private),
+                    parsed.find(domain.as_bytes(), ignore_private),
+                    "{:?} with ignore_private={}", domain, ignore_private
+                );
+            }
+        }
+    }
+
+    // The process-wide default list is shared with every other test running in parallel, so the
+    // swap is exercised on a local instance of the same holder.
+    #[test]
+    fn test_default_list_swap() {
+        let default_list = DefaultList::new(PublicSuffixList::builtin());
+        let in_flight = default_list.get();
+        assert_eq!(get_registrable_domain(&domain("www.example.co.uk"), Some(&in_flight), false), Some("example.co.uk".to_string()));
+
+        let rules: String = include_str!("../data/psl.dat").to_string() + &icann_rules("example.co.uk\n");
+        let previous = default_list.replace(rules.parse().unwrap());
+        assert!(Arc::ptr_eq(&previous, &in_flight));
+        assert_eq!(get_registrable_domain(&domain("www.example.co.uk"), Some(&default_list.get()), false), Some("www.example.co.uk".to_string()));
+        assert_eq!(get_registrable_domain(&domain("www.example.co.uk"), Some(&in_flight), false), Some("example.co.uk".to_string()));
+
+        default_list.replace(PublicSuffixList::builtin());
+        assert_eq!(get_registrable_domain(&domain("www.example.co.uk"), Some(&default_list.get()), false), Some("example.co.uk".to_string()));
+    }
+}
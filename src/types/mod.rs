@@ -1 +1,3 @@
+// `types::types` is part of the public paths, so the module keeps its name.
+#[allow(clippy::module_inception)]
 pub mod types;
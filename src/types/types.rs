@@ -1,8 +1,10 @@
-use std::{collections::HashMap};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
-use crate::errors::HostError;
+use crate::errors::{HostError, ValidationError};
+use crate::psl_trie::PslNode;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum IPAddress {
     IPv4(u32),
     IPv6(u128),
@@ -32,27 +34,30 @@ impl Host {
 }
 
 pub type IPv4 = u32;
-pub type Ipv4NumberResult = Result<(u8, bool), HostError>;
+pub type Ipv4NumberResult = Result<(u64, bool), HostError>;
 
 pub struct Ipv6Address(pub u128);
 pub type Ipv6Pieces = [u16; 8];
 
+impl From<Ipv6Pieces> for Ipv6Address {
+    fn from(value: Ipv6Pieces) -> Self {
+        let address: u128 = value
+            .iter()
+            .enumerate()
+            .fold(0, |address, (index, piece)| address | (*piece as u128) << (index * 16));
+
+        Ipv6Address(address)
+    }
+}
+
 impl From<Ipv6Address> for Ipv6Pieces {
     fn from(value: Ipv6Address) -> Self {
-        [
-            (value.0 >> (0 * 16) & 0xFFFF) as u16,
-            (value.0 >> (1 * 16) & 0xFFFF) as u16,
-            (value.0 >> (2 * 16) & 0xFFFF) as u16,
-            (value.0 >> (3 * 16) & 0xFFFF) as u16,
-            (value.0 >> (4 * 16) & 0xFFFF) as u16,
-            (value.0 >> (5 * 16) & 0xFFFF) as u16,
-            (value.0 >> (6 * 16) & 0xFFFF) as u16,
-            (value.0 >> (7 * 16) & 0xFFFF) as u16,
-        ]
+        std::array::from_fn(|index| (value.0 >> (index * 16) & 0xFFFF) as u16)
     }
 }
 
-enum UrlSearchParamTypes {
+// Reference: https://url.spec.whatwg.org/#interface-urlsearchparams
+pub enum UrlSearchParamTypes {
     SeqT(Vec<(String, String)>),
     RecT(HashMap<String, String>),
     StrT(String),
@@ -60,11 +65,10 @@ enum UrlSearchParamTypes {
 
 pub struct URLSearchParams {
     list: Vec<(String, String)>,
-    size: u32
 }
 
 impl URLSearchParams {
-    fn init(init: UrlSearchParamTypes) -> Self {
+    pub fn init(init: UrlSearchParamTypes) -> Self {
         let list: Vec<(String, String)> = match init {
             UrlSearchParamTypes::SeqT(seq) => {
                 seq
@@ -78,74 +82,87 @@ impl URLSearchParams {
                     .map(|(x,y)| (x.to_owned(), y.to_owned()))
                     .collect::<Vec<(String, String)>>()
             }
-            UrlSearchParamTypes::StrT(seq) => { 
-                todo!("Add support for UrlSearchParamTypes::StrT");
+            UrlSearchParamTypes::StrT(seq) => {
+                let query: &str = seq.strip_prefix('?').unwrap_or(&seq);
+                return URLSearchParams::from_string(query.to_string());
             }
         };
 
-        return URLSearchParams {
-            size: list.len() as u32,
-            list,
-        }
+        URLSearchParams { list }
     }
 
-    fn size(self: Self) -> u32 {
-        return self.size;
+    pub fn size(&self) -> usize {
+        self.list.len()
     } 
 
-    fn append(name: String, value: String) {}
-    fn delete(name: String, value: Option<String>) {}
+    pub fn append(&mut self, name: &str, value: &str) {
+        self.list.push((name.to_string(), value.to_string()));
+    }
+
+    pub fn delete(&mut self, name: &str, value: Option<&str>) {
+        self.list.retain(|(n, v)| n != name || value.is_some_and(|value| v != value));
+    }
 
-    fn get(self: Self, name: String) -> Option<String> {
-        for tuple in self.list {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        for tuple in &self.list {
             if tuple.0 == name {
-                return Some(tuple.1);
+                return Some(&tuple.1);
             }
         }
 
-        return None;
+        None
     }
 
-    fn get_all(self: Self, name: String) -> Vec<String> {
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
         self.list
             .iter()
-            .filter(|(f, s)| { f == &name })
-            .map(|(first, second)| second.to_owned())
+            .filter(|(f, _)| { f == name })
+            .map(|(_, second)| second.as_str())
             .collect()
     }
 
-    fn has(self: Self, name: String, value: Option<String>) -> bool {
-        for element in self.list {
-            if element.0 == name {
-                if let Some(v) = value {
-                    if element.1 == v {
-                        return true;
-                    }
-                }
+    pub fn has(&self, name: &str, value: Option<&str>) -> bool {
+        self.list
+            .iter()
+            .any(|(n, v)| n == name && value.is_none_or(|value| v == value))
+    }
 
+    // Sets the value of the first pair named `name` and removes the others, or appends a pair if
+    // there is none.
+    pub fn set(&mut self, name: &str, value: &str) {
+        let mut found: bool = false;
+        self.list.retain_mut(|(n, v)| {
+            if n != name {
                 return true;
+            } else if found {
+                return false;
             }
+
+            found = true;
+            *v = value.to_string();
+            true
+        });
+
+        if !found {
+            self.append(name, value);
         }
-        return false;
     }
 
-    fn set(name: String, value: String) {}
-
     // TODO: Extract this into a separate application/x-www-form-urlencoded de/serializer
-    fn to_string(self: Self) -> String {
+    pub fn serialize(&self) -> String {
         let mut output: String = String::new();
 
-        for seq in self.list {
-            if output.is_empty() {
+        for seq in &self.list {
+            if !output.is_empty() {
                 output += "&";
             }
             output += &format!("{}={}", seq.0, seq.1).to_string();
         }
 
-        return output;
+        output
     }
 
-    fn from_string(input: String) -> Self {
+    pub fn from_string(input: String) -> Self {
         let sequences: Vec<String> = input.split("&").map(|s| s.to_string()).collect();
         let mut output: Vec<(String, String)> = Vec::new();
 
@@ -171,14 +188,22 @@ impl URLSearchParams {
             output.push((name, value));
         } 
 
-        return URLSearchParams {
-            size: output.len() as u32,
-            list: output,
-        };
+        URLSearchParams { list: output }
     }
 
+    pub fn pairs(&self) -> &[(String, String)] {
+        &self.list
+    }
+}
+
+// Reference: https://url.spec.whatwg.org/#concept-url-path
+#[derive(Clone, Debug, PartialEq)]
+pub enum Path {
+    Opaque(String),
+    Segments(Vec<String>),
 }
 
+#[derive(Clone)]
 pub struct URL {
     pub href: String,
     pub origin: String,
@@ -190,11 +215,45 @@ pub struct URL {
     pub host: Option<Host>,
     pub hostname: String,
     pub port: Option<u16>,
-    pub path: Vec<String>,
+    pub(crate) path: Path,
     pub search: String,
     pub hash: String,
 }
 
+// Options for the basic URL parser and the host parser. The defaults are those of the URL
+// Standard; see src/options.rs for the builder methods.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub(crate) check_hyphens: bool,
+    pub(crate) check_bidi: bool,
+    pub(crate) check_joiners: bool,
+    pub(crate) use_std3_ascii_rules: bool,
+    pub(crate) transitional_processing: bool,
+    pub(crate) verify_dns_length: bool,
+    pub(crate) strict: bool,
+    pub(crate) limits: ResourceLimits,
+}
+
+// Upper bounds on the input the basic URL parser accepts, checked before the work they bound is
+// done. `None` means unbounded, which is the default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ResourceLimits {
+    pub(crate) max_input_length: Option<usize>,
+    pub(crate) max_path_segments: Option<usize>,
+    pub(crate) max_host_length: Option<usize>,
+    pub(crate) max_query_length: Option<usize>,
+    pub(crate) max_search_params: Option<usize>,
+}
+
+// A validation error and the byte range of the parser's input it applies to, before ASCII tabs
+// and newlines were removed. An empty range points between two code points, e.g. at a missing
+// U+002F(/).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub error: ValidationError,
+    pub span: Range<usize>,
+}
+
 #[derive(Clone)]
 pub enum UrlParseState {
     SchemeStart,
@@ -219,3 +278,171 @@ pub enum UrlParseState {
     Query,
     Fragment,
 }
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MimeType {
+    pub type_: String,
+    pub subtype: String,
+    pub parameters: Vec<(String, String)>,
+}
+
+// A domain with its labels reversed, e.g. "com.example.www". It is ordered label by label rather
+// than byte by byte, so that every subdomain sorts right after its parent domain even when a
+// sibling label contains a U+002D(-).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReverseDnsKey(pub(crate) String);
+
+// A host-only cookie is only sent back to the exact host that set it, while a domain cookie is
+// also sent to its subdomains.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CookieDomain {
+    HostOnly(String),
+    Domain(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostSuffixMatch {
+    Exact,
+    IncludeSubdomains,
+}
+
+// Domain entries are stored as a trie keyed by labels from the top-level domain down, so a lookup
+// walks at most one node per label of the host.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HostSuffixNode {
+    pub(crate) children: HashMap<String, HostSuffixNode>,
+    pub(crate) entry: Option<HostSuffixMatch>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HostSuffixSet {
+    pub(crate) root: HostSuffixNode,
+    pub(crate) addresses: HashSet<IPAddress>,
+    pub(crate) len: usize,
+}
+
+// An address block in CIDR notation, e.g. "10.0.0.0/8". The address has no bits set beyond
+// the prefix.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IpNetwork {
+    pub(crate) address: IPAddress,
+    pub(crate) prefix_len: u8,
+}
+
+// Sorted, non-overlapping and non-adjacent inclusive ranges of numeric addresses. IPv6 ranges
+// use the address's numeric value, with the first piece in the most significant bits.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IpRangeSet {
+    pub(crate) ipv4: Vec<(u32, u32)>,
+    pub(crate) ipv6: Vec<(u128, u128)>,
+}
+
+// Why a label is shown in its A-label form instead of its U-label form.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdnFallbackReason {
+    InvalidPunycode,
+    DisallowedCharacter(char),
+    MixedScript,
+    WholeScriptConfusable,
+    ScriptNotAllowedForTld,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayLabel {
+    pub value: String,
+    pub fallback: Option<IdnFallbackReason>,
+}
+
+// Reference: https://publicsuffix.org/list/
+pub struct PublicSuffixList {
+    pub(crate) rules: PublicSuffixRules,
+}
+
+pub(crate) enum PublicSuffixRules {
+    // The trie compiled from data/psl.dat by build.rs.
+    Compiled,
+    // The same tables built from a list loaded at runtime.
+    Parsed {
+        labels: String,
+        nodes: Vec<PslNode>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PublicSuffixSection {
+    Icann,
+    Private,
+}
+
+// The byte length and section of the public suffix of a domain found by
+// `PublicSuffixList::find`. `section` is None if no rule matched and the top-level label was used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SuffixMatch {
+    pub len: usize,
+    pub section: Option<PublicSuffixSection>,
+}
+
+// `section` is None and `is_known` is false when only the implicit "*" rule matched.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicSuffix {
+    pub value: String,
+    pub section: Option<PublicSuffixSection>,
+    pub is_known: bool,
+}
+
+// Reference: https://www.rfc-editor.org/rfc/rfc6068#section-2
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MailtoUrl {
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    // Any other header fields, in the order they appeared.
+    pub headers: Vec<(String, String)>,
+}
+
+// Mutable view over a URL's path segments that keeps them percent-encoded and normalized the way
+// the parser would.
+pub struct PathSegmentsMut<'a> {
+    pub(crate) url: &'a mut URL,
+    pub(crate) has_pushed: bool,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_search_params_serialize() {
+        let params = URLSearchParams::from_string("a=1&&b+c=d=e&f".to_string());
+        assert_eq!(params.pairs(), [("a".to_string(), "1".to_string()), ("b c".to_string(), "d=e".to_string()), ("f".to_string(), "".to_string())]);
+        assert_eq!(params.serialize(), "a=1&b c=d=e&f=");
+        assert_eq!(URLSearchParams::from_string(params.serialize()).pairs(), params.pairs());
+    }
+
+    #[test]
+    fn test_url_search_params() {
+        let mut params = URLSearchParams::init(UrlSearchParamTypes::StrT("?a=1&b=2&a=3".to_string()));
+        assert_eq!(params.size(), 3);
+        assert_eq!(params.get("a"), Some("1"));
+        assert_eq!(params.get_all("a"), ["1", "3"]);
+        assert!(params.has("b", None));
+        assert!(params.has("a", Some("3")));
+        assert!(!params.has("a", Some("2")));
+
+        params.set("a", "4");
+        assert_eq!(params.serialize(), "a=4&b=2");
+        params.set("c", "5");
+        params.append("b", "6");
+        assert_eq!(params.serialize(), "a=4&b=2&c=5&b=6");
+        params.delete("b", Some("2"));
+        assert_eq!(params.serialize(), "a=4&c=5&b=6");
+        params.delete("b", None);
+        assert_eq!(params.serialize(), "a=4&c=5");
+
+        let params = URLSearchParams::init(UrlSearchParamTypes::SeqT(vec![("x".to_string(), "y".to_string())]));
+        assert_eq!(params.serialize(), "x=y");
+    }
+}
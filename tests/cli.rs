@@ -0,0 +1,35 @@
+use std::process::{Command, Output};
+
+fn resloc(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_resloc")).args(args).output().unwrap()
+}
+
+// Validation errors of URLs that parse are not printed, so stdout holds one JSON object per URL
+// and nothing else.
+#[test]
+fn test_json_output() {
+    let output = resloc(&["--json", "http://EXAMPLE.com\\a b/%zz", "https://xn--bcher-kva.de:443/\t?q"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+
+    let stdout: String = String::from_utf8(output.stdout).unwrap();
+    let hrefs: Vec<String> = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["href"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(hrefs, ["http://example.com/a%20b/%zz", "https://xn--bcher-kva.de/?q"]);
+}
+
+// A URL that fails is reported once on stderr, and as a JSON object with its error on stdout.
+#[test]
+fn test_failure_output() {
+    let output = resloc(&["--json", "http://exa mple.com/"]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stdout["input"], "http://exa mple.com/");
+    assert!(stdout["error"].as_str().unwrap().starts_with("domain-invalid-code-point"));
+
+    let stderr: String = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("http://exa mple.com/").count(), 1, "{}", stderr);
+}